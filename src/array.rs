@@ -0,0 +1,112 @@
+use super::UnionFindOp;
+use core::cell::Cell;
+
+/// A union-find over a fixed number of elements stored inline, so it never allocates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArrayUnionFind<const N: usize> {
+    ptrs: [Cell<u32>; N],
+    len: usize,
+}
+
+impl<const N: usize> Default for ArrayUnionFind<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> ArrayUnionFind<N> {
+    pub fn new() -> Self {
+        assert!(N < u32::MAX as usize, "ArrayUnionFind<{N}> will overflow");
+        Self {
+            ptrs: core::array::from_fn(|i| Cell::new(i as u32)),
+            len: N,
+        }
+    }
+    #[inline]
+    pub fn get(&self, mut v: usize) -> usize {
+        while let n = self.ptrs[v].get() as usize
+            && n != v
+        {
+            v = n;
+        }
+        v
+    }
+    #[inline]
+    pub fn get_compress(&self, v: usize) -> usize {
+        let dst = self.get(v);
+        self.ptrs[v].set(dst as u32);
+        dst
+    }
+    pub fn set(&mut self, v: usize, to: usize) -> bool {
+        let root_to = self.get_compress(to);
+        let root_v = self.get_compress(v);
+        if root_v == root_to {
+            return false;
+        }
+        self.ptrs[root_v].set(root_to as u32);
+        self.len -= 1;
+        true
+    }
+    /// Checks if a vertex is itself the root of a tree
+    pub fn is_root(&self, v: usize) -> bool {
+        self.ptrs
+            .get(v)
+            .map(|p| p.get() as usize == v)
+            .unwrap_or(false)
+    }
+}
+
+impl<const N: usize> UnionFindOp for ArrayUnionFind<N> {
+    #[inline]
+    fn find(&self, v: usize) -> usize {
+        self.get_compress(v)
+    }
+    #[inline]
+    fn find_no_compress(&self, v: usize) -> usize {
+        self.get(v)
+    }
+    #[inline]
+    fn is_root(&self, v: usize) -> bool {
+        self.is_root(v)
+    }
+    #[inline]
+    fn union(&mut self, v: usize, to: usize) -> bool {
+        self.set(v, to)
+    }
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+    #[inline]
+    fn capacity(&self) -> usize {
+        N
+    }
+}
+
+#[test]
+fn test_array_union_find() {
+    let mut uf = ArrayUnionFind::<8>::new();
+    assert_eq!(uf.len(), 8);
+    assert!(uf.set(0, 1));
+    assert!(uf.set(2, 1));
+    assert!(!uf.set(0, 2));
+    uf.union_chain(&[5, 6, 7]);
+    assert_eq!(uf.len(), 4);
+    assert!(uf.same_set(0, 2) && uf.same_set(5, 7));
+    assert!(!uf.same_set(2, 5));
+    assert!(uf.is_root(3) && uf.is_root(4));
+    // Stored inline, so the structure is exactly its pointers and length.
+    assert_eq!(
+        size_of::<ArrayUnionFind<8>>(),
+        size_of::<[u32; 8]>() + size_of::<usize>()
+    );
+    assert_eq!(ArrayUnionFind::<0>::default().len(), 0);
+}
+
+#[test]
+fn test_array_is_root_out_of_range() {
+    let uf = ArrayUnionFind::<4>::new();
+    assert!(uf.is_root(3));
+    assert!(!uf.is_root(4));
+    assert!(!uf.is_root(usize::MAX));
+}
@@ -1,48 +1,349 @@
-use super::UnionFindOp;
-use core::sync::atomic::AtomicU32;
+use super::{CapacityError, UnionFindOp};
+use alloc::collections::TryReserveError;
+use alloc::{vec, vec::Vec};
+use core::cell::Cell;
 use core::sync::atomic::Ordering;
+#[cfg(not(loom))]
+use core::sync::atomic::{AtomicU32, AtomicUsize};
+#[cfg(loom)]
+use loom::sync::atomic::{AtomicU32, AtomicUsize};
+
+/// Ordering of loads in [`UnionFind::union_shared`]. `Acquire` is sufficient, since a root is
+/// only ever published by a successful `compare_exchange`, which releases it.
+#[cfg(not(feature = "seqcst"))]
+const LOAD: Ordering = Ordering::Acquire;
+/// Ordering of the `compare_exchange` which links a root in [`UnionFind::union_shared`].
+#[cfg(not(feature = "seqcst"))]
+const LINK: Ordering = Ordering::AcqRel;
+#[cfg(feature = "seqcst")]
+const LOAD: Ordering = Ordering::SeqCst;
+#[cfg(feature = "seqcst")]
+const LINK: Ordering = Ordering::SeqCst;
 
 #[derive(Debug)]
 pub struct UnionFind {
     ptrs: Vec<AtomicU32>,
-    len: usize,
+    len: AtomicUsize,
+    /// Ordering of loads in `get`.
+    load: Ordering,
+    /// Ordering of stores in `get_compress` and `set`.
+    store: Ordering,
+}
+
+/// Takes a snapshot by loading each element in turn, which is not atomic as a whole if other
+/// threads are concurrently unioning.
+impl Clone for UnionFind {
+    fn clone(&self) -> Self {
+        Self {
+            ptrs: self
+                .ptrs
+                .iter()
+                .map(|p| AtomicU32::new(p.load(self.load)))
+                .collect(),
+            len: AtomicUsize::new(self.len.load(Ordering::Relaxed)),
+            load: self.load,
+            store: self.store,
+        }
+    }
+}
+
+/// Compares the parent of every element and the number of components, ignoring orderings.
+impl PartialEq for UnionFind {
+    fn eq(&self, other: &Self) -> bool {
+        self.len.load(Ordering::Relaxed) == other.len.load(Ordering::Relaxed)
+            && self.ptrs.len() == other.ptrs.len()
+            && self
+                .ptrs
+                .iter()
+                .zip(&other.ptrs)
+                .all(|(a, b)| a.load(self.load) == b.load(other.load))
+    }
+}
+
+impl Eq for UnionFind {}
+
+impl From<UnionFind> for super::UnionFind<u32> {
+    fn from(uf: UnionFind) -> Self {
+        let ptrs = uf
+            .ptrs
+            .iter()
+            .map(|p| Cell::new(p.load(uf.load)))
+            .collect::<Vec<_>>();
+        let mut sizes = vec![0; ptrs.len()];
+        for i in 0..ptrs.len() {
+            sizes[uf.get(i)] += 1;
+        }
+        Self {
+            ptrs,
+            sizes,
+            len: uf.len() as u32,
+            halve: false,
+            growable: false,
+        }
+    }
+}
+
+/// Converts with `SeqCst` orderings, as in [`UnionFind::new`].
+impl From<super::UnionFind<u32>> for UnionFind {
+    fn from(uf: super::UnionFind<u32>) -> Self {
+        Self {
+            ptrs: uf.ptrs.iter().map(|p| AtomicU32::new(p.get())).collect(),
+            len: AtomicUsize::new(uf.curr_len()),
+            load: Ordering::SeqCst,
+            store: Ordering::SeqCst,
+        }
+    }
+}
+
+impl Default for UnionFind {
+    fn default() -> Self {
+        Self::new(0)
+    }
 }
 
 impl UnionFind {
     #[inline]
     pub fn new(len: usize) -> Self {
+        Self::with_ordering(len, Ordering::SeqCst, Ordering::SeqCst)
+    }
+    /// Creates `len` singletons, the same as [`Self::new`].
+    #[inline]
+    pub fn with_capacity(len: usize) -> Self {
+        Self::new(len)
+    }
+    /// Creates a union-find whose `get`, `get_compress`, and `set` use the given orderings.
+    /// `union_shared` is unaffected.
+    pub fn with_ordering(len: usize, load: Ordering, store: Ordering) -> Self {
+        assert!(
+            !matches!(load, Ordering::Release | Ordering::AcqRel),
+            "{load:?} is not a valid load ordering"
+        );
+        assert!(
+            !matches!(store, Ordering::Acquire | Ordering::AcqRel),
+            "{store:?} is not a valid store ordering"
+        );
         assert!(len < u32::MAX as usize, "UnionFind<u32> will overflow");
         let ptrs = (0..len).map(|_| AtomicU32::new(0)).collect::<Vec<_>>();
         for (i, ptr) in ptrs.iter().enumerate() {
             ptr.store(i as u32, Ordering::SeqCst);
         }
-        Self { ptrs, len }
+        Self {
+            ptrs,
+            len: AtomicUsize::new(len),
+            load,
+            store,
+        }
+    }
+    /// Checks if a vertex is itself the root of a tree
+    #[inline]
+    pub fn is_root(&self, v: usize) -> bool {
+        self.ptrs
+            .get(v)
+            .map(|p| p.load(self.load) as usize == v)
+            .unwrap_or(false)
+    }
+    #[inline]
+    pub fn get(&self, mut v: usize) -> usize {
+        while let n = idx!(self.ptrs, v).load(self.load) as usize
+            && n != v
+        {
+            v = n;
+        }
+        v
     }
+    /// Finds the root of `v` with `Relaxed` loads and no stores, so that many threads may call
+    /// it without contending on cache lines. Paths are never shortened, so prefer
+    /// [`Self::get_compress`] unless the forest is no longer changing.
     #[inline]
-    pub fn get(&self, v: usize) -> usize {
-        let mut v = v as u32;
-        while let n = unsafe { self.ptrs.get_unchecked(v as usize) }.load(Ordering::SeqCst)
+    pub fn find_readonly(&self, mut v: usize) -> usize {
+        while let n = idx!(self.ptrs, v).load(Ordering::Relaxed) as usize
             && n != v
         {
             v = n;
         }
-        v as usize
+        v
     }
+    /// Finds the root of `v`, pointing `v` directly at it. This may run concurrently with
+    /// [`Self::union_shared`] and other calls to `get_compress`: a root is never overwritten,
+    /// since that could undo a concurrent link, and `v` is only repointed if its parent has not
+    /// changed since it was read, so a fresher pointer from another thread is never replaced
+    /// with a staler one.
     pub fn get_compress(&self, v: usize) -> usize {
+        let parent = idx!(self.ptrs, v).load(self.load) as usize;
+        if parent == v {
+            return v;
+        }
+        let dst = self.get(parent);
+        self.repoint(v, parent, dst);
+        dst
+    }
+    /// Points `v` at `dst` if its parent is still `parent`, for compressing concurrently.
+    #[inline]
+    fn repoint(&self, v: usize, parent: usize, dst: usize) {
+        if dst != parent {
+            // Failing means another thread already moved `v` closer to the root.
+            let _ = idx!(self.ptrs, v).compare_exchange(
+                parent as u32,
+                dst as u32,
+                self.store,
+                self.load,
+            );
+        }
+    }
+    /// Same as [`Self::get_compress`], but with a plain store since no other thread can access
+    /// the structure.
+    fn get_compress_mut(&mut self, v: usize) -> usize {
         let dst = self.get(v);
-        unsafe { self.ptrs.get_unchecked(v) }.store(dst as u32, Ordering::SeqCst);
+        if dst != v {
+            idx!(self.ptrs, v).store(dst as u32, self.store);
+        }
         dst
     }
-    // safe since this union find is exclusively held, and cannot be updated in parallel.
-    pub fn set(&mut self, v: usize, to: usize) {
-        debug_assert!(v <= self.ptrs.len());
-        debug_assert!(to <= self.ptrs.len());
-        let root_to = self.get_compress(to);
-        let root_v = self.get_compress(v);
-        if root_v != root_to {
-            unsafe { self.ptrs.get_unchecked(root_v) }.store(root_to as u32, Ordering::SeqCst);
-            self.len -= 1;
+    /// Checks whether `a` and `b` are in the same component without compressing.
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        let n = self.ptrs.len();
+        assert!(
+            a < n && b < n,
+            "({a}, {b}) is out of range for {n} elements"
+        );
+        self.get(a) == self.get(b)
+    }
+    /// Unions `v` and `to` without atomic read-modify-writes, which is safe since this union
+    /// find is exclusively held. Panics if either is out of bounds, unless the `unchecked`
+    /// feature is enabled.
+    pub fn set(&mut self, v: usize, to: usize) -> bool {
+        #[cfg(not(feature = "unchecked"))]
+        {
+            assert!(v < self.ptrs.len(), "{v} is out of bounds");
+            assert!(to < self.ptrs.len(), "{to} is out of bounds");
+        }
+        let root_to = self.get_compress_mut(to);
+        let root_v = self.get_compress_mut(v);
+        if root_v == root_to {
+            return false;
+        }
+        idx!(self.ptrs, root_v).store(root_to as u32, self.store);
+        self.len.fetch_sub(1, Ordering::Relaxed);
+        true
+    }
+    /// Finds the root of `v`, compressing it as in [`Self::get_compress`], or `None` if `v` is
+    /// out of bounds. Always bounds checks, even with the `unchecked` feature.
+    pub fn try_find(&self, v: usize) -> Option<usize> {
+        let parent = self.ptrs.get(v)?.load(self.load) as usize;
+        let mut root = parent;
+        while let p = self.ptrs.get(root)?.load(self.load) as usize
+            && p != root
+        {
+            root = p;
+        }
+        self.repoint(v, parent, root);
+        Some(root)
+    }
+    /// Unions `v` and `to` as in [`Self::set`], or returns `None` if either is out of bounds.
+    pub fn try_union(&mut self, v: usize, to: usize) -> Option<bool> {
+        let root_to = self.try_find(to)?;
+        let root_v = self.try_find(v)?;
+        if root_v == root_to {
+            return Some(false);
         }
+        self.ptrs[root_v].store(root_to as u32, self.store);
+        self.len.fetch_sub(1, Ordering::Relaxed);
+        Some(true)
+    }
+    /// Appends `n` new singletons, returning their indices.
+    pub fn extend_by(&mut self, n: usize) -> core::ops::Range<usize> {
+        match self.try_extend_by(n) {
+            Ok(r) => r,
+            Err(e) => panic!("UnionFind<u32> will overflow: {e}"),
+        }
+    }
+    /// Appends `n` new singletons, returning their indices, or an error without modifying
+    /// `self` if there would be too many elements or they cannot be allocated.
+    pub fn try_extend_by(&mut self, n: usize) -> Result<core::ops::Range<usize>, CapacityError> {
+        let l = self.ptrs.len();
+        match l.checked_add(n) {
+            Some(total) if total < u32::MAX as usize => {}
+            _ => {
+                return Err(CapacityError::Overflow {
+                    requested: l.saturating_add(n),
+                    max: u32::MAX as usize - 1,
+                });
+            }
+        }
+        self.try_reserve(n)?;
+        self.ptrs
+            .extend((l..l + n).map(|i| AtomicU32::new(i as u32)));
+        self.len.fetch_add(n, Ordering::Relaxed);
+        Ok(l..l + n)
+    }
+    /// Same as [`Self::reserve`], but returns an error instead of aborting if the storage
+    /// cannot be allocated.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.ptrs.try_reserve(additional)
+    }
+    /// Points every element directly at its root.
+    pub fn compress(&mut self) {
+        for i in 0..self.ptrs.len() {
+            self.get_compress_mut(i);
+        }
+    }
+    /// Compresses every element, then returns the root of each, so that a read only phase can
+    /// index a plain slice instead of contending on the atomics.
+    pub fn finalize(&mut self) -> Vec<u32> {
+        self.compress();
+        self.ptrs.iter().map(|p| p.load(self.load)).collect()
+    }
+    /// Reserves storage for at least `additional` more elements without adding any.
+    pub fn reserve(&mut self, additional: usize) {
+        self.ptrs.reserve(additional);
+    }
+    /// Releases any excess capacity of the backing storage.
+    pub fn shrink_to_fit(&mut self) {
+        self.ptrs.shrink_to_fit();
+    }
+    /// Approximate number of bytes allocated on the heap, including excess capacity.
+    pub fn heap_bytes(&self) -> usize {
+        self.ptrs.capacity() * size_of::<AtomicU32>()
+    }
+    /// Makes every element a singleton again, reusing the existing allocation.
+    pub fn reset(&mut self) {
+        for (i, ptr) in self.ptrs.iter().enumerate() {
+            ptr.store(i as u32, self.store);
+        }
+        self.len.store(self.ptrs.len(), Ordering::Relaxed);
+    }
+    /// Unions `v` and `to`, and may be called from many threads at once. Returns whether this
+    /// call performed the merge.
+    ///
+    /// Roots are always linked from the lower index to the higher one, so concurrent links can
+    /// never form a cycle. If a root is re-rooted by another thread before it is linked, the
+    /// `compare_exchange` fails and the roots are found again.
+    pub fn union_shared(&self, v: usize, to: usize) -> bool {
+        assert!(v < self.ptrs.len() && to < self.ptrs.len());
+        loop {
+            let rv = self.root(v);
+            let rt = self.root(to);
+            if rv == rt {
+                return false;
+            }
+            let (lo, hi) = if rv < rt { (rv, rt) } else { (rt, rv) };
+            if self.ptrs[lo]
+                .compare_exchange(lo as u32, hi as u32, LINK, LOAD)
+                .is_ok()
+            {
+                self.len.fetch_sub(1, Ordering::Relaxed);
+                return true;
+            }
+        }
+    }
+    /// Finds the root of `v` without compressing, using the same ordering as `union_shared`.
+    fn root(&self, mut v: usize) -> usize {
+        while let n = self.ptrs[v].load(LOAD) as usize
+            && n != v
+        {
+            v = n;
+        }
+        v
     }
 }
 
@@ -50,13 +351,337 @@ impl UnionFindOp for UnionFind {
     fn find(&self, v: usize) -> usize {
         self.get_compress(v)
     }
-    fn union(&mut self, v: usize, to: usize) {
-        self.set(v, to);
+    fn find_no_compress(&self, v: usize) -> usize {
+        self.get(v)
+    }
+    fn is_root(&self, v: usize) -> bool {
+        self.is_root(v)
+    }
+    fn union(&mut self, v: usize, to: usize) -> bool {
+        self.set(v, to)
     }
     fn len(&self) -> usize {
-        self.len
+        self.len.load(Ordering::Relaxed)
     }
     fn capacity(&self) -> usize {
         self.ptrs.len()
     }
 }
+
+#[test]
+fn test_union_shared() {
+    let uf = UnionFind::new(64);
+    std::thread::scope(|s| {
+        for t in 0..4 {
+            let uf = &uf;
+            s.spawn(move || {
+                for i in (t..63).step_by(4) {
+                    uf.union_shared(i, i + 1);
+                }
+            });
+        }
+    });
+    assert_eq!(uf.len(), 1);
+    assert!((0..64).all(|i| uf.get(i) == uf.get(0)));
+}
+
+#[test]
+fn test_reset() {
+    let mut uf = UnionFind::new(6);
+    assert!(uf.set(0, 1));
+    assert!(!uf.union(1, 0));
+    uf.union_shared(2, 3);
+    uf.union_shared(1, 3);
+    uf.reset();
+    assert_eq!(uf.len(), uf.capacity());
+    assert!((0..6).all(|i| uf.get(i) == i));
+}
+
+#[test]
+fn test_try_find_union() {
+    let mut uf = UnionFind::new(4);
+    assert_eq!(uf.try_union(0, 1), Some(true));
+    assert_eq!(uf.try_union(1, 0), Some(false));
+    assert_eq!(uf.try_union(2, 4), None);
+    assert_eq!(uf.len(), 3);
+    assert_eq!(uf.try_find(0), Some(1));
+    assert_eq!(uf.try_find(4), None);
+}
+
+#[test]
+fn test_default_with_capacity() {
+    assert_eq!(UnionFind::default().capacity(), 0);
+    let uf = UnionFind::with_capacity(8);
+    assert_eq!((uf.capacity(), uf.len()), (8, 8));
+}
+
+#[test]
+fn test_clone_eq() {
+    let mut uf = UnionFind::new(8);
+    uf.set(0, 1);
+    uf.union_shared(2, 3);
+    uf.union_shared(1, 3);
+    let snapshot = uf.clone();
+    assert_eq!(snapshot, uf);
+    assert_eq!(snapshot.len(), 5);
+    uf.set(6, 7);
+    assert_ne!(snapshot, uf);
+    assert_ne!(snapshot.get(6), snapshot.get(7));
+}
+
+#[test]
+fn test_convert() {
+    let uf = UnionFind::new(8);
+    uf.union_shared(0, 1);
+    uf.union_shared(2, 1);
+    uf.union_shared(6, 5);
+    let cell: super::UnionFind<u32> = uf.clone().into();
+    assert_eq!(cell.curr_len(), uf.len());
+    assert!((0..8).all(|i| cell.get(i) == uf.get(i)));
+    assert_eq!(cell.size_of(2), 3);
+
+    let mut cell = cell;
+    cell.set(7, 0);
+    let back = UnionFind::from(cell.clone());
+    assert_eq!(back.len(), cell.curr_len());
+    assert!((0..8).all(|i| back.get(i) == cell.get(i)));
+}
+
+#[test]
+fn test_shrink_to_fit() {
+    let mut uf = UnionFind::new(8);
+    uf.ptrs.reserve(100);
+    let before = uf.heap_bytes();
+    uf.shrink_to_fit();
+    assert!(uf.heap_bytes() < before);
+    assert_eq!(uf.heap_bytes(), 8 * 4);
+}
+
+#[test]
+fn test_extend_by() {
+    let mut uf = UnionFind::new(3);
+    uf.set(0, 1);
+    let r = uf.extend_by(4);
+    assert_eq!(r, 3..7);
+    assert!(r.clone().all(|i| uf.get(i) == i));
+    assert_eq!(uf.len(), 6);
+    assert!(uf.union_shared(6, 0));
+}
+
+#[test]
+fn test_compress() {
+    let parents = super::UnionFind::from_parents(vec![1, 2, 3, 4, 4, 6, 6]).unwrap();
+    let mut uf = UnionFind::from(parents);
+    uf.extend_by(8);
+    assert_eq!(uf.len(), 10);
+    uf.compress();
+    assert_eq!(uf.len(), 10);
+    for i in 0..uf.capacity() {
+        let p = uf.ptrs[i].load(Ordering::SeqCst) as usize;
+        assert_eq!(p, uf.get(i));
+    }
+    assert_eq!(uf.get(0), 4);
+}
+
+#[test]
+fn test_finalize() {
+    let parents = super::UnionFind::from_parents(vec![1, 2, 3, 3, 3, 7, 5, 7]).unwrap();
+    let mut uf = UnionFind::from(parents);
+    let roots = uf.finalize();
+    assert!((0..8).all(|i| roots[i] as usize == uf.get(i)));
+    assert!(roots.iter().all(|&r| roots[r as usize] == r));
+    assert_eq!(roots, [3, 3, 3, 3, 3, 7, 7, 7]);
+}
+
+#[test]
+fn test_find_readonly() {
+    let parents = super::UnionFind::from_parents((1..64).chain([63]).collect()).unwrap();
+    let uf = UnionFind::from(parents);
+    let before = uf.clone();
+    for i in 0..64 {
+        assert_eq!(uf.find_readonly(i), 63);
+    }
+    assert_eq!(uf, before);
+    assert_eq!(uf.ptrs[0].load(Ordering::SeqCst), 1);
+
+    std::thread::scope(|s| {
+        let handles = (0..8)
+            .map(|t| {
+                let uf = &uf;
+                s.spawn(move || (0..1000).all(|i| uf.find_readonly((i * 7 + t) % 64) == 63))
+            })
+            .collect::<Vec<_>>();
+        for h in handles {
+            assert!(h.join().unwrap());
+        }
+    });
+    assert_eq!(uf, before);
+}
+
+#[test]
+fn test_try_extend_by() {
+    let mut uf = UnionFind::new(4);
+    uf.set(0, 1);
+    assert!(uf.try_reserve(usize::MAX).is_err());
+    assert!(matches!(
+        uf.try_extend_by(u32::MAX as usize),
+        Err(CapacityError::Overflow { .. })
+    ));
+    assert_eq!((uf.capacity(), uf.len()), (4, 3));
+    assert_eq!(uf.try_extend_by(2), Ok(4..6));
+    assert_eq!(uf.len(), 5);
+}
+
+#[test]
+fn test_reserve() {
+    let mut uf = UnionFind::new(4);
+    uf.reserve(50);
+    let bytes = uf.heap_bytes();
+    assert_eq!(uf.len(), 4);
+    uf.extend_by(50);
+    assert_eq!(uf.heap_bytes(), bytes);
+}
+
+#[cfg(not(feature = "unchecked"))]
+#[test]
+#[should_panic(expected = "4 is out of bounds")]
+fn test_set_out_of_bounds() {
+    let mut uf = UnionFind::new(4);
+    uf.set(1, 4);
+}
+
+/// Indices past `u32::MAX` must not be truncated onto a valid element.
+#[cfg(all(target_pointer_width = "64", not(feature = "unchecked")))]
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_get_truncated_index() {
+    UnionFind::new(4).get((1 << 32) | 1);
+}
+
+#[cfg(all(target_pointer_width = "64", not(feature = "unchecked")))]
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_find_readonly_truncated_index() {
+    UnionFind::new(4).find_readonly((1 << 32) | 1);
+}
+
+#[test]
+fn test_with_ordering() {
+    let mut uf = UnionFind::with_ordering(8, Ordering::Relaxed, Ordering::Release);
+    uf.set(0, 1);
+    uf.set(2, 1);
+    uf.set(5, 6);
+    assert_eq!(uf.len(), 5);
+    assert_eq!(uf.get(0), 1);
+    assert_eq!(uf.get_compress(2), 1);
+    assert!(uf.connected(5, 6));
+    assert!(!uf.connected(0, 5));
+}
+
+#[test]
+#[should_panic(expected = "not a valid store ordering")]
+fn test_with_ordering_invalid() {
+    UnionFind::with_ordering(4, Ordering::Acquire, Ordering::Acquire);
+}
+
+#[test]
+fn test_union_shared_random() {
+    let n = 512;
+    let pairs = |t: u64| {
+        let mut x = (t + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        (0..128).map(move |_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            ((x % n as u64) as usize, ((x >> 32) % n as u64) as usize)
+        })
+    };
+    let uf = UnionFind::new(n);
+    std::thread::scope(|s| {
+        for t in 0..8 {
+            let uf = &uf;
+            s.spawn(move || {
+                for (a, b) in pairs(t) {
+                    uf.union_shared(a, b);
+                    uf.get_compress(a);
+                }
+            });
+        }
+    });
+    let mut expected = super::UnionFind::new(n);
+    for t in 0..8 {
+        for (a, b) in pairs(t) {
+            expected.union(a, b);
+        }
+    }
+    let roots = (0..n).filter(|&i| uf.get(i) == i).count();
+    assert_eq!(uf.len(), roots);
+    assert_eq!(uf.len(), expected.len());
+    for i in 0..n {
+        assert_eq!(uf.get(i), uf.get(uf.get(i)));
+        assert_eq!(uf.connected(i, 0), expected.same_set(i, 0));
+    }
+}
+
+#[test]
+fn test_concurrent_get_compress() {
+    let n = 256;
+    let uf = UnionFind::new(n);
+    // A single long chain, so that compressions from different threads race on each element.
+    for i in 0..n - 1 {
+        uf.union_shared(i, i + 1);
+    }
+    let before = (0..n)
+        .map(|i| uf.ptrs[i].load(Ordering::SeqCst) as usize)
+        .collect::<Vec<_>>();
+    std::thread::scope(|s| {
+        for t in 0..8 {
+            let uf = &uf;
+            s.spawn(move || {
+                for k in 0..4 * n {
+                    let i = (k * 31 + t * 17) % n;
+                    if t % 2 == 0 {
+                        uf.get_compress(i);
+                    } else {
+                        uf.try_find(i);
+                    }
+                    if k % 64 == t {
+                        uf.union_shared(i, (i + t) % n);
+                    }
+                }
+            });
+        }
+    });
+    for (i, &prev) in before.iter().enumerate() {
+        let p = uf.ptrs[i].load(Ordering::SeqCst) as usize;
+        // Every element points at an ancestor, which is at least as far along the chain as its
+        // original parent.
+        assert!(p >= prev, "{i} regressed from {prev} to {p}");
+        assert_eq!(uf.get(p), uf.get(i));
+        assert_eq!(uf.get(i), n - 1);
+    }
+}
+
+#[test]
+fn test_is_root_out_of_range() {
+    let uf = UnionFind::new(4);
+    assert!(uf.is_root(3));
+    assert!(!uf.is_root(4));
+    assert!(!uf.is_root(usize::MAX));
+}
+
+#[cfg(loom)]
+#[test]
+fn test_loom_union_shared() {
+    loom::model(|| {
+        let uf = loom::sync::Arc::new(UnionFind::new(3));
+        let threads = [(0, 1), (2, 1)].map(|(a, b)| {
+            let uf = uf.clone();
+            loom::thread::spawn(move || uf.union_shared(a, b))
+        });
+        let merged = threads.map(|t| t.join().unwrap());
+        assert_eq!(merged, [true, true]);
+        assert_eq!(uf.len(), 1);
+        assert_eq!(uf.root(0), uf.root(2));
+    });
+}
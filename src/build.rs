@@ -0,0 +1,163 @@
+use crate::{UfIndex, UnionFind};
+use alloc::collections::TryReserveError;
+use alloc::{vec, vec::Vec};
+use core::cell::Cell;
+
+/// Returned when a parent array does not describe a valid forest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildError {
+    /// There are more elements than the index type can address.
+    TooLarge(usize),
+    /// The parent of `index` is not a valid element.
+    OutOfBounds { index: usize, parent: usize },
+    /// Following parents from `index` loops without reaching a root.
+    Cycle { index: usize },
+}
+
+impl core::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            BuildError::TooLarge(n) => write!(f, "{n} elements is too many for index type"),
+            BuildError::OutOfBounds { index, parent } => {
+                write!(f, "parent {parent} of {index} is out of bounds")
+            }
+            BuildError::Cycle { index } => write!(f, "cycle through {index}"),
+        }
+    }
+}
+
+impl core::error::Error for BuildError {}
+
+/// Returned when growing fails, in which case nothing was modified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CapacityError {
+    /// There would be `requested` elements, more than the `max` the index type can address.
+    Overflow { requested: usize, max: usize },
+    /// The storage could not be allocated.
+    Alloc(TryReserveError),
+}
+
+impl From<TryReserveError> for CapacityError {
+    fn from(e: TryReserveError) -> Self {
+        CapacityError::Alloc(e)
+    }
+}
+
+impl core::fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CapacityError::Overflow { requested, max } => {
+                write!(f, "{requested} elements exceeds the maximum of {max}")
+            }
+            CapacityError::Alloc(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl core::error::Error for CapacityError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            CapacityError::Overflow { .. } => None,
+            CapacityError::Alloc(e) => Some(e),
+        }
+    }
+}
+
+impl<T: UfIndex> UnionFind<T> {
+    /// Builds from a parent array, checking that every pointer is in bounds and eventually
+    /// reaches a root, then recomputing sizes and the number of sets.
+    pub(crate) fn try_from_parents(ptrs: Vec<T>) -> Result<Self, BuildError> {
+        let n = ptrs.len();
+        if n >= T::MAX {
+            return Err(BuildError::TooLarge(n));
+        }
+        if let Some((index, p)) = ptrs.iter().enumerate().find(|(_, p)| p.to_usize() >= n) {
+            return Err(BuildError::OutOfBounds {
+                index,
+                parent: p.to_usize(),
+            });
+        }
+
+        // Resolve the root of every element, rejecting pointer cycles which would never terminate.
+        const UNSEEN: usize = usize::MAX;
+        const ON_PATH: usize = usize::MAX - 1;
+        let mut root = vec![UNSEEN; n];
+        let mut path = vec![];
+        for i in 0..n {
+            let mut curr = i;
+            while root[curr] == UNSEEN {
+                let next = ptrs[curr].to_usize();
+                if next == curr {
+                    root[curr] = curr;
+                    break;
+                }
+                root[curr] = ON_PATH;
+                path.push(curr);
+                curr = next;
+            }
+            if root[curr] == ON_PATH {
+                return Err(BuildError::Cycle { index: curr });
+            }
+            let r = root[curr];
+            for p in path.drain(..) {
+                root[p] = r;
+            }
+        }
+
+        let mut sizes = vec![0; n];
+        for &r in &root {
+            sizes[r] += 1;
+        }
+        let num_roots = (0..n).filter(|&i| root[i] == i).count();
+        Ok(Self {
+            ptrs: ptrs.into_iter().map(Cell::new).collect(),
+            sizes: sizes.into_iter().map(T::from_usize).collect(),
+            len: T::from_usize(num_roots),
+            halve: false,
+            growable: false,
+        })
+    }
+}
+
+impl UnionFind<u32> {
+    /// Builds a union-find directly from each element's parent, where roots point to
+    /// themselves. This is the inverse of [`UnionFind::parents_slice`].
+    pub fn from_parents(parents: Vec<u32>) -> Result<Self, BuildError> {
+        Self::try_from_parents(parents)
+    }
+}
+
+#[test]
+fn test_from_parents() {
+    use crate::UnionFindOp;
+    let uf = UnionFind::from_parents(vec![0, 0, 1, 3, 3, 5]).unwrap();
+    assert_eq!(uf.len(), 3);
+    assert_eq!(uf.capacity(), 6);
+    assert!((0..3).all(|i| uf.get(i) == 0));
+    assert_eq!(uf.get(4), 3);
+    assert_eq!(uf.get(5), 5);
+    assert_eq!(uf.sizes[0], 3);
+
+    let mut uf = UnionFind::from_parents(vec![]).unwrap();
+    assert!(uf.is_empty());
+    assert_eq!(uf.parents_slice(), &[] as &[u32]);
+}
+
+#[test]
+fn test_from_parents_out_of_bounds() {
+    assert_eq!(
+        UnionFind::from_parents(vec![0, 3, 2]),
+        Err(BuildError::OutOfBounds {
+            index: 1,
+            parent: 3
+        })
+    );
+}
+
+#[test]
+fn test_from_parents_cycle() {
+    assert!(matches!(
+        UnionFind::from_parents(vec![0, 2, 1]),
+        Err(BuildError::Cycle { .. })
+    ));
+}
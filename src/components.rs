@@ -0,0 +1,1150 @@
+use super::{UfIndex, UnionFind, UnionFindOp};
+use alloc::collections::BTreeMap;
+use alloc::{vec, vec::Vec};
+use core::cell::Cell;
+use core::cmp::Ordering;
+use core::hash::Hasher;
+use core::iter::FusedIterator;
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::hash::Hash;
+
+impl<T: Copy + Eq> UnionFind<T>
+where
+    Self: UnionFindOp,
+{
+    /// Groups all elements by component in CSR form, returning `(offsets, members)`, where the
+    /// members of component `k` are `members[offsets[k]..offsets[k + 1]]`.
+    pub(crate) fn csr(&self) -> (Vec<usize>, Vec<usize>) {
+        let mut offsets = vec![];
+        let mut members = vec![];
+        self.groups_into(&mut members, &mut offsets);
+        (offsets, members)
+    }
+    /// Groups all elements by component into reusable buffers, such that the members of
+    /// component `k` are `perm[offsets[k]..offsets[k + 1]]`. Components are ordered by first
+    /// appearance, and members are in ascending order.
+    pub fn groups_into(&self, perm: &mut Vec<usize>, offsets: &mut Vec<usize>) {
+        let n = self.capacity();
+        let mut label = vec![usize::MAX; n];
+        offsets.clear();
+        offsets.push(0);
+        // temporarily store each element's label in `perm`.
+        perm.clear();
+        for i in 0..n {
+            let r = self.find(i);
+            if label[r] == usize::MAX {
+                label[r] = offsets.len() - 1;
+                offsets.push(0);
+            }
+            offsets[label[r] + 1] += 1;
+            perm.push(label[r]);
+        }
+        for k in 1..offsets.len() {
+            offsets[k] += offsets[k - 1];
+        }
+        // reuse `label` as the fill cursor of each component.
+        label.clear();
+        label.extend_from_slice(&offsets[..offsets.len() - 1]);
+        let labels = core::mem::replace(perm, vec![0; n]);
+        for (i, l) in labels.into_iter().enumerate() {
+            perm[label[l]] = i;
+            label[l] += 1;
+        }
+    }
+    /// Returns the members of every component.
+    pub fn groups(&self) -> Vec<Vec<usize>> {
+        self.component_iter().collect()
+    }
+    /// Every equivalence class, one per component with each element in exactly one. This is
+    /// the same as [`Self::groups`].
+    pub fn partition(&self) -> Vec<Vec<usize>> {
+        self.groups()
+    }
+    /// Lazily yields the members of each component, one at a time.
+    pub fn component_iter(&self) -> impl Iterator<Item = Vec<usize>> + '_ {
+        let (offsets, members) = self.csr();
+        (0..offsets.len() - 1).map(move |k| members[offsets[k]..offsets[k + 1]].to_vec())
+    }
+    /// Returns the `(root, members)` of every component, ordered from largest to smallest, with
+    /// ties broken by the smaller root.
+    pub fn components_by_size(&self) -> Vec<(usize, Vec<usize>)> {
+        let mut comps = self
+            .component_iter()
+            .map(|c| (self.find(c[0]), c))
+            .collect::<Vec<_>>();
+        comps.sort_unstable_by(|(ra, a), (rb, b)| b.len().cmp(&a.len()).then(ra.cmp(rb)));
+        comps
+    }
+    /// Lazily iterates over each component's root and members, without allocating. Each
+    /// component's members are found by scanning every element, so a full traversal is
+    /// O(n * components).
+    pub fn iter_components(&self) -> ComponentsIter<'_, T> {
+        ComponentsIter { uf: self, next: 0 }
+    }
+    /// Returns all members of the component containing `v` in ascending order. This scans every
+    /// element, so prefer [`Self::components`] when listing every component.
+    pub fn members_of(&self, v: usize) -> Vec<usize> {
+        let root = self.find(v);
+        (0..self.capacity())
+            .filter(|&i| self.find(i) == root)
+            .collect()
+    }
+    /// Groups every element by component in a single pass. Keys are the roots, and each value
+    /// holds all members in ascending order, including the root itself.
+    #[cfg(feature = "std")]
+    pub fn components(&self) -> HashMap<usize, Vec<usize>> {
+        let mut out = HashMap::<usize, Vec<usize>>::new();
+        for i in 0..self.capacity() {
+            out.entry(self.find(i)).or_default().push(i);
+        }
+        out
+    }
+    /// Returns the `(root, members)` of the first component, ordered by root, which satisfies
+    /// `pred(root, members)`. Components after the first match are never visited.
+    pub fn find_component<F: Fn(usize, &[usize]) -> bool>(
+        &self,
+        pred: F,
+    ) -> Option<(usize, Vec<usize>)> {
+        self.iter_components()
+            .map(|(r, members)| (r, members.collect::<Vec<_>>()))
+            .find(|(r, members)| pred(*r, members))
+    }
+    /// Unions `v` and `to`, and returns all members of the merged component in ascending order.
+    /// This scans every element, so it is O(n).
+    pub fn union_and_members(&mut self, v: usize, to: usize) -> Vec<usize> {
+        self.union(v, to);
+        let root = self.find(to);
+        (0..self.capacity())
+            .filter(|&i| self.find(i) == root)
+            .collect()
+    }
+    /// Unions every singleton other than `into` into the component containing `into`, returning
+    /// the number of singletons merged. This may link many elements, so it is O(n).
+    pub fn collect_singletons(&mut self, into: usize) -> usize {
+        let n = self.capacity();
+        let mut sizes = vec![0usize; n];
+        for i in 0..n {
+            sizes[self.find(i)] += 1;
+        }
+        let singletons = (0..n)
+            .filter(|&i| i != into && sizes[i] == 1)
+            .collect::<Vec<_>>();
+        for &i in &singletons {
+            self.union(i, into);
+        }
+        singletons.len()
+    }
+    /// Returns all members of every component containing one of `seeds`, in ascending order.
+    pub fn seeded_component(&self, seeds: &[usize]) -> Vec<usize> {
+        let mut is_seeded = vec![false; self.capacity()];
+        for &s in seeds {
+            is_seeded[self.find(s)] = true;
+        }
+        (0..self.capacity())
+            .filter(|&i| is_seeded[self.find(i)])
+            .collect()
+    }
+    /// Returns the `(root, size)` of every component, ordered by root.
+    pub fn summary(&self) -> Vec<(u32, u32)> {
+        let mut sizes = vec![0u32; self.capacity()];
+        for i in 0..self.capacity() {
+            sizes[self.find(i)] += 1;
+        }
+        sizes
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, s)| s > 0)
+            .map(|(r, s)| (r as u32, s))
+            .collect()
+    }
+    /// Returns the rank of each element's component when components are ordered by root, so
+    /// elements with smaller roots always have smaller ranks.
+    pub fn component_rank(&self) -> Vec<u32> {
+        let n = self.capacity();
+        let mut rank = vec![0u32; n];
+        let mut next = 0;
+        for (i, r) in rank.iter_mut().enumerate() {
+            if self.find(i) == i {
+                *r = next;
+                next += 1;
+            }
+        }
+        (0..n).map(|i| rank[self.find(i)]).collect()
+    }
+    /// Labels each element with its component's id in `0..len()`, with ids assigned in order of
+    /// each component's first element.
+    pub fn component_labels(&self) -> Vec<usize> {
+        let labeling = self.labeling();
+        (0..self.capacity()).map(|i| labeling.label_of(i)).collect()
+    }
+    /// A bitmap of the members of `v`'s component, where element `i` is bit `i % 64` of word
+    /// `i / 64`, so that membership can be checked without finding roots.
+    pub fn component_bitset(&self, v: usize) -> Vec<u64> {
+        let n = self.capacity();
+        let root = self.find(v);
+        let mut bits = vec![0u64; n.div_ceil(64)];
+        for i in (0..n).filter(|&i| self.find(i) == root) {
+            bits[i / 64] |= 1 << (i % 64);
+        }
+        bits
+    }
+    /// Precomputes the same ids as [`Self::component_labels`] for each root, so that single
+    /// elements can be labeled cheaply with [`Labeling::label_of`].
+    pub fn labeling(&self) -> Labeling<'_, T> {
+        let n = self.capacity();
+        let mut root_label = vec![usize::MAX; n];
+        let mut next = 0;
+        for i in 0..n {
+            let r = self.find(i);
+            if root_label[r] == usize::MAX {
+                root_label[r] = next;
+                next += 1;
+            }
+        }
+        Labeling {
+            uf: self,
+            root_label,
+        }
+    }
+    /// Returns each component as a range of indices, ordered by start, if every component is
+    /// contiguous.
+    pub fn components_as_ranges(&self) -> Option<Vec<Range<usize>>> {
+        let (offsets, members) = self.csr();
+        offsets
+            .windows(2)
+            .map(|w| {
+                // members of each component are in ascending order.
+                let (first, last) = (members[w[0]], members[w[1] - 1]);
+                (last - first + 1 == w[1] - w[0]).then_some(first..last + 1)
+            })
+            .collect()
+    }
+    /// Returns the sorted indices at which each component starts, if every component is
+    /// contiguous.
+    pub fn boundaries(&self) -> Option<Vec<usize>> {
+        let ranges = self.components_as_ranges()?;
+        Some(ranges.into_iter().map(|r| r.start).collect())
+    }
+    /// Builds a new union-find over an arbitrary set of elements, preserving their mutual
+    /// connectivity. Also returns the mapping from each new index to its original index.
+    pub fn extract_components(&self, members: &[usize]) -> (UnionFind<u32>, Vec<usize>) {
+        let mut out = UnionFind::<u32>::with_capacity(members.len());
+        let mut first = vec![usize::MAX; self.capacity()];
+        for (i, &m) in members.iter().enumerate() {
+            let r = self.find(m);
+            if first[r] == usize::MAX {
+                first[r] = i;
+            }
+            out.union(i, first[r]);
+        }
+        (out, members.to_vec())
+    }
+    /// Builds a dense union-find over only the elements not marked in `deleted`, preserving
+    /// their connectivity. Also returns the new index of each original element, which is `None`
+    /// for deleted elements. This structure does not track deletions itself, so the caller
+    /// provides which elements are deleted.
+    pub fn compact_deleted(&self, deleted: &[bool]) -> (UnionFind<u32>, Vec<Option<u32>>) {
+        assert_eq!(deleted.len(), self.capacity());
+        let live = (0..self.capacity())
+            .filter(|&i| !deleted[i])
+            .collect::<Vec<_>>();
+        let (out, map) = self.extract_components(&live);
+        let mut remap = vec![None; self.capacity()];
+        for (new_i, old_i) in map.into_iter().enumerate() {
+            remap[old_i] = Some(new_i as u32);
+        }
+        (out, remap)
+    }
+    /// Splits components into two new union-finds according to `pred(root, size)`, with kept
+    /// components first and rejected components second. Also returns the mapping from new index to
+    /// original index, with the rejected structure's indices offset by the kept capacity.
+    pub fn partition_by<F: Fn(usize, usize) -> bool>(
+        &self,
+        pred: F,
+    ) -> (UnionFind<u32>, UnionFind<u32>, Vec<usize>) {
+        let (offsets, members) = self.csr();
+        let mut kept = vec![];
+        let mut rejected = vec![];
+        for w in offsets.windows(2) {
+            let comp = &members[w[0]..w[1]];
+            if pred(self.find(comp[0]), comp.len()) {
+                kept.extend_from_slice(comp);
+            } else {
+                rejected.extend_from_slice(comp);
+            }
+        }
+        let (kept, mut map) = self.extract_components(&kept);
+        let (rejected, rejected_map) = self.extract_components(&rejected);
+        map.extend(rejected_map);
+        (kept, rejected, map)
+    }
+    /// Applies the connectivity of `other` to `self`, so that the result is the join of both
+    /// partitions. Both must have the same capacity.
+    pub fn union_with(&mut self, other: &Self) {
+        assert_eq!(self.capacity(), other.capacity());
+        for i in 0..other.capacity() {
+            let r = other.find(i);
+            if r != i {
+                self.union(i, r);
+            }
+        }
+    }
+    /// Consuming version of [`Self::union_with`].
+    pub fn absorb(&mut self, other: Self) {
+        self.union_with(&other);
+    }
+    /// Places the elements of `other` after those of `self` in a new union-find, preserving the
+    /// connectivity of both without connecting them to each other.
+    pub fn concat(&self, other: &UnionFind<u32>) -> UnionFind<u32> {
+        let offset = self.capacity();
+        let mut out = UnionFind::<u32>::with_capacity(offset + other.capacity());
+        for (a, b) in self.closure_star_edges() {
+            out.union(a as usize, b as usize);
+        }
+        for (a, b) in other.closure_star_edges() {
+            out.union(a as usize + offset, b as usize + offset);
+        }
+        out
+    }
+    /// Checks whether both structures represent the same partition, regardless of which
+    /// elements are roots.
+    pub fn same_partition(&self, other: &Self) -> bool {
+        let n = self.capacity();
+        if n != other.capacity() {
+            return false;
+        }
+        let mut to_other = vec![usize::MAX; n];
+        let mut to_self = vec![usize::MAX; n];
+        (0..n).all(|i| {
+            let (a, b) = (self.find(i), other.find(i));
+            if to_other[a] == usize::MAX && to_self[b] == usize::MAX {
+                to_other[a] = b;
+                to_self[b] = a;
+            }
+            to_other[a] == b && to_self[b] == a
+        })
+    }
+    /// Checks whether both structures connect the elements of `r` in the same way, ignoring how
+    /// elements outside of `r` are connected.
+    pub fn subset_same_partition(&self, other: &UnionFind<u32>, r: Range<usize>) -> bool {
+        let mut to_other = BTreeMap::new();
+        let mut to_self = BTreeMap::new();
+        r.into_iter().all(|i| {
+            let (a, b) = (self.find(i), other.find(i));
+            *to_other.entry(a).or_insert(b) == b && *to_self.entry(b).or_insert(a) == a
+        })
+    }
+    /// Returns the merges which turn `prev` into `self`, as pairs of `prev` roots. Each component
+    /// of `self` which covers `k` components of `prev` contributes `k - 1` pairs, linking the
+    /// first `prev` root seen to each of the others. `self` must be coarser than `prev`.
+    pub fn merges_between(&self, prev: &Self) -> Vec<(usize, usize)> {
+        assert_eq!(self.capacity(), prev.capacity());
+        let mut first = vec![usize::MAX; self.capacity()];
+        let mut out = vec![];
+        for i in 0..self.capacity() {
+            let p = prev.find(i);
+            if p != i {
+                continue;
+            }
+            let r = self.find(i);
+            if first[r] == usize::MAX {
+                first[r] = p;
+            } else {
+                out.push((first[r], p));
+            }
+        }
+        out
+    }
+    /// Splits each component so that two elements stay connected only if they were connected
+    /// and have the same `key`. Each new component is rooted at its smallest member.
+    #[cfg(feature = "std")]
+    pub fn refine_by<K: Eq + Hash, F: Fn(usize) -> K>(&mut self, key: F)
+    where
+        T: UfIndex,
+    {
+        let n = self.capacity();
+        let mut reps = HashMap::new();
+        let new_rep = (0..n)
+            .map(|i| *reps.entry((self.find(i), key(i))).or_insert(i))
+            .collect::<Vec<_>>();
+        let mut sizes = vec![0; n];
+        for (i, &r) in new_rep.iter().enumerate() {
+            self.ptrs[i].set(T::from_usize(r));
+            sizes[r] += 1;
+        }
+        for (s, c) in self.sizes.iter_mut().zip(sizes) {
+            *s = T::from_usize(c);
+        }
+        self.len = T::from_usize(reps.len());
+    }
+    /// Checks whether both structures have the same multiset of component sizes, regardless of
+    /// which elements are in each component.
+    pub fn same_size_profile(&self, other: &Self) -> bool {
+        let profile = |uf: &Self| {
+            let mut sizes = uf.summary().into_iter().map(|(_, s)| s).collect::<Vec<_>>();
+            sizes.sort_unstable();
+            sizes
+        };
+        profile(self) == profile(other)
+    }
+    /// Relabels every element `i` to `perm[i]`, preserving connectivity. `perm` must be a
+    /// permutation of `0..capacity()`.
+    pub fn permute(&self, perm: &[usize]) -> UnionFind<u32> {
+        let n = self.capacity();
+        assert_eq!(perm.len(), n, "Permutation has the wrong length");
+        let mut seen = vec![false; n];
+        for &p in perm {
+            assert!(
+                p < n && !seen[p],
+                "Not a permutation, {p} is invalid or repeated"
+            );
+            seen[p] = true;
+        }
+        let mut out = UnionFind::<u32>::with_capacity(n);
+        for (i, &p) in perm.iter().enumerate() {
+            out.union(p, perm[self.find(i)]);
+        }
+        out
+    }
+}
+
+impl UnionFind<u32> {
+    /// Returns the join of every partition in `parts`, so that two elements are connected if
+    /// they are connected in any of them. All parts must have the same capacity.
+    pub fn join_all(parts: &[&UnionFind<u32>]) -> UnionFind<u32> {
+        let n = parts.first().map_or(0, |p| p.capacity());
+        let mut out = UnionFind::<u32>::with_capacity(n);
+        for p in parts {
+            assert_eq!(p.capacity(), n, "Cannot join parts of different capacities");
+            for (a, b) in p.closure_star_edges() {
+                out.union(a as usize, b as usize);
+            }
+        }
+        out
+    }
+}
+
+/// Hashes the partition rather than the parent pointers, so structures with the same partition
+/// hash equally even though `==` compares pointers and considers them different. Hashing never
+/// compresses, since that could change which structures are equal. For the same reason, keys
+/// of a map should not be compressed by calling `find` through a shared reference.
+impl<T: UfIndex> core::hash::Hash for UnionFind<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.capacity());
+        state.write_usize(self.len.to_usize());
+        for l in self.labels_no_compress() {
+            state.write_usize(l);
+        }
+    }
+}
+
+impl<T: UfIndex> UnionFind<T> {
+    /// Same as [`Self::component_labels`], but never compresses.
+    fn labels_no_compress(&self) -> Vec<usize> {
+        let n = self.capacity();
+        let mut label = vec![usize::MAX; n];
+        let mut next = 0;
+        (0..n)
+            .map(|i| {
+                let r = self.get(i);
+                if label[r] == usize::MAX {
+                    label[r] = next;
+                    next += 1;
+                }
+                label[r]
+            })
+            .collect()
+    }
+}
+
+/// Orders by partition first, comparing [`UnionFind::component_labels`] lexicographically, so
+/// sorting places structures with the same partition next to each other. Ties are broken by the
+/// parent pointers and remaining fields, so that the order agrees with `==`, which compares
+/// pointers rather than partitions. Like hashing, comparing never compresses.
+impl<T: UfIndex + Ord> Ord for UnionFind<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.labels_no_compress()
+            .cmp(&other.labels_no_compress())
+            .then_with(|| {
+                self.ptrs
+                    .iter()
+                    .map(Cell::get)
+                    .cmp(other.ptrs.iter().map(Cell::get))
+            })
+            .then_with(|| self.sizes.cmp(&other.sizes))
+            .then_with(|| self.len.cmp(&other.len))
+            .then_with(|| self.halve.cmp(&other.halve))
+            .then_with(|| self.growable.cmp(&other.growable))
+    }
+}
+
+impl<T: UfIndex + Ord> PartialOrd for UnionFind<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Dense component ids, created by [`UnionFind::labeling`]. This borrows the union-find, so it
+/// cannot go stale from later unions.
+#[derive(Debug, Clone)]
+pub struct Labeling<'a, T: Copy + Eq = usize> {
+    uf: &'a UnionFind<T>,
+    /// The id of each root, or `usize::MAX` for non-roots.
+    root_label: Vec<usize>,
+}
+
+impl<T: Copy + Eq> Labeling<'_, T>
+where
+    UnionFind<T>: UnionFindOp,
+{
+    /// The id of `v`'s component in `0..len()`, equal to `component_labels()[v]`.
+    pub fn label_of(&self, v: usize) -> usize {
+        self.root_label[self.uf.find(v)]
+    }
+}
+
+/// Iterator over the components of a union-find, created by [`UnionFind::iter_components`].
+#[derive(Debug, Clone)]
+pub struct ComponentsIter<'a, T: Copy + Eq = usize> {
+    uf: &'a UnionFind<T>,
+    /// The next element to check for being a root.
+    next: usize,
+}
+
+impl<'a, T: Copy + Eq> Iterator for ComponentsIter<'a, T>
+where
+    UnionFind<T>: UnionFindOp,
+{
+    type Item = (usize, ComponentMembers<'a, T>);
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next < self.uf.capacity() {
+            let i = self.next;
+            self.next += 1;
+            if self.uf.find(i) == i {
+                let members = ComponentMembers {
+                    uf: self.uf,
+                    root: i,
+                    next: 0,
+                };
+                return Some((i, members));
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over the members of a single component in ascending order.
+#[derive(Debug, Clone)]
+pub struct ComponentMembers<'a, T: Copy + Eq = usize> {
+    uf: &'a UnionFind<T>,
+    root: usize,
+    next: usize,
+}
+
+impl<T: Copy + Eq> Iterator for ComponentMembers<'_, T>
+where
+    UnionFind<T>: UnionFindOp,
+{
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        while self.next < self.uf.capacity() {
+            let i = self.next;
+            self.next += 1;
+            if self.uf.find(i) == self.root {
+                return Some(i);
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over `(element, root)` for every element, without compressing.
+#[derive(Debug, Clone)]
+pub struct ElementRoots<'a, T: Copy + Eq = usize> {
+    uf: &'a UnionFind<T>,
+    elems: Range<usize>,
+}
+
+impl<T: UfIndex> Iterator for ElementRoots<'_, T> {
+    type Item = (usize, usize);
+    fn next(&mut self) -> Option<(usize, usize)> {
+        self.elems.next().map(|i| (i, self.uf.get(i)))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.elems.size_hint()
+    }
+}
+
+impl<T: UfIndex> ExactSizeIterator for ElementRoots<'_, T> {}
+
+/// Iterator over the root of every component in ascending order, without compressing, created
+/// by [`UnionFind::roots`].
+#[derive(Debug, Clone)]
+pub struct Roots<'a, T: Copy + Eq = usize> {
+    uf: &'a UnionFind<T>,
+    elems: Range<usize>,
+    /// Number of roots not yet yielded.
+    remaining: usize,
+}
+
+impl<T: UfIndex> UnionFind<T> {
+    /// Lazily yields the root of every component in ascending order, without compressing.
+    pub fn roots(&self) -> Roots<'_, T> {
+        Roots {
+            uf: self,
+            elems: 0..self.capacity(),
+            remaining: self.curr_len(),
+        }
+    }
+}
+
+impl<T: UfIndex> Iterator for Roots<'_, T> {
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let r = self.elems.find(|&i| self.uf.is_root(i))?;
+        self.remaining -= 1;
+        Some(r)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T: UfIndex> ExactSizeIterator for Roots<'_, T> {}
+
+impl<T: UfIndex> FusedIterator for Roots<'_, T> {}
+
+impl<'a, T: UfIndex> IntoIterator for &'a UnionFind<T> {
+    type Item = (usize, usize);
+    type IntoIter = ElementRoots<'a, T>;
+    fn into_iter(self) -> ElementRoots<'a, T> {
+        ElementRoots {
+            uf: self,
+            elems: 0..self.capacity(),
+        }
+    }
+}
+
+#[test]
+fn test_component_iter() {
+    let mut uf = UnionFind::new(10);
+    uf.union(0, 9);
+    uf.union(3, 4);
+    uf.union(4, 9);
+    let mut streamed = uf
+        .component_iter()
+        .map(|mut c| {
+            c.sort_unstable();
+            c
+        })
+        .collect::<Vec<_>>();
+    let mut groups = uf.groups();
+    for g in groups.iter_mut() {
+        g.sort_unstable();
+    }
+    streamed.sort();
+    groups.sort();
+    assert_eq!(streamed, groups);
+    assert_eq!(groups.len(), uf.curr_len());
+    assert!(groups.contains(&vec![0, 3, 4, 9]));
+}
+
+#[test]
+fn test_extract_components() {
+    let mut uf = UnionFind::new(16);
+    uf.union(1, 9);
+    uf.union(9, 14);
+    uf.union(3, 7);
+    let (sub, map) = uf.extract_components(&[14, 3, 1, 12, 7]);
+    assert_eq!(map, vec![14, 3, 1, 12, 7]);
+    assert_eq!(sub.curr_len(), 3);
+    for i in 0..map.len() {
+        for j in 0..map.len() {
+            assert_eq!(sub.get(i) == sub.get(j), uf.get(map[i]) == uf.get(map[j]));
+        }
+    }
+}
+
+#[test]
+fn test_components_as_ranges() {
+    let mut uf = UnionFind::new(7);
+    uf.union(0, 1);
+    uf.union(2, 1);
+    uf.union(4, 5);
+    uf.union(6, 5);
+    assert_eq!(uf.components_as_ranges(), Some(vec![0..3, 3..4, 4..7]));
+    uf.union(3, 0);
+    uf.union(1, 6);
+    let ranges = uf.components_as_ranges().unwrap();
+    assert_eq!(ranges.len(), 1);
+    assert_eq!(ranges[0], 0..7);
+
+    let mut uf = UnionFind::new(4);
+    uf.union(0, 2);
+    assert_eq!(uf.components_as_ranges(), None);
+}
+
+#[test]
+fn test_permute() {
+    let mut uf = UnionFind::new(6);
+    uf.union(0, 1);
+    uf.union(1, 4);
+    uf.union(2, 3);
+    let perm = (0..6).rev().collect::<Vec<_>>();
+    let p = uf.permute(&perm);
+    assert_eq!(p.curr_len(), uf.curr_len());
+    for i in 0..6 {
+        for j in 0..6 {
+            assert_eq!(uf.get(i) == uf.get(j), p.get(perm[i]) == p.get(perm[j]));
+        }
+    }
+}
+
+#[test]
+fn test_absorb() {
+    let mut a = UnionFind::<u32>::with_capacity(8);
+    a.union(0, 1);
+    a.union(4, 5);
+    let mut b = UnionFind::<u32>::with_capacity(8);
+    b.union(1, 2);
+    b.union(6, 7);
+
+    let mut joined = a.clone();
+    joined.union_with(&b);
+    a.absorb(b);
+    assert_eq!(a.curr_len(), 4);
+    assert_eq!(a.groups(), joined.groups());
+}
+
+#[test]
+fn test_partition_by() {
+    let mut uf = UnionFind::new(8);
+    uf.union(1, 2);
+    uf.union_chain(&[4, 6, 7]);
+    let (kept, rejected, map) = uf.partition_by(|_, size| size > 1);
+    assert_eq!(kept.capacity(), 5);
+    assert_eq!(kept.curr_len(), 2);
+    assert_eq!(rejected.capacity(), 3);
+    assert_eq!(rejected.curr_len(), 3);
+    assert_eq!(map, vec![1, 2, 4, 6, 7, 0, 3, 5]);
+    assert_eq!(kept.get(0), kept.get(1));
+    assert_eq!(kept.get(2), kept.get(4));
+    assert_ne!(kept.get(0), kept.get(2));
+}
+
+#[test]
+fn test_summary() {
+    let mut uf = UnionFind::new(9);
+    uf.union_chain(&[0, 4, 8]);
+    uf.union(2, 3);
+    let summary = uf.summary();
+    assert_eq!(summary.len(), uf.curr_len());
+    assert_eq!(
+        summary.iter().map(|&(_, s)| s as usize).sum::<usize>(),
+        uf.capacity()
+    );
+    assert!(summary.contains(&(8, 3)));
+    assert!(summary.contains(&(3, 2)));
+}
+
+#[test]
+fn test_union_and_members() {
+    let mut uf = UnionFind::new(8);
+    uf.union(1, 6);
+    uf.union(3, 4);
+    assert_eq!(uf.union_and_members(6, 3), vec![1, 3, 4, 6]);
+    assert_eq!(uf.union_and_members(0, 0), vec![0]);
+}
+
+#[test]
+fn test_boundaries() {
+    let mut uf = UnionFind::new(8);
+    uf.union_runs(&[(0, 2), (2, 3), (6, 2)]);
+    assert_eq!(uf.boundaries(), Some(vec![0, 2, 5, 6]));
+    uf.union(0, 7);
+    assert_eq!(uf.boundaries(), None);
+}
+
+#[test]
+fn test_component_rank() {
+    let mut uf = UnionFind::new(8);
+    uf.union(0, 5);
+    uf.union(6, 2);
+    uf.union(7, 2);
+    let rank = uf.component_rank();
+    assert_eq!(rank, vec![4, 0, 1, 2, 3, 4, 1, 1]);
+    for i in 0..8 {
+        for j in 0..8 {
+            if uf.get(i) < uf.get(j) {
+                assert!(rank[i] < rank[j]);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_collect_singletons() {
+    let mut uf = UnionFind::new(8);
+    uf.union(0, 1);
+    uf.union_chain(&[4, 5, 6]);
+    assert_eq!(uf.collect_singletons(5), 3);
+    assert_eq!(uf.curr_len(), 2);
+    for i in [2, 3, 4, 6, 7] {
+        assert!(uf.same_set(i, 5));
+    }
+    assert!(!uf.same_set(0, 5));
+    assert_eq!(uf.collect_singletons(5), 0);
+}
+
+#[test]
+fn test_seeded_component() {
+    let mut uf = UnionFind::new(10);
+    uf.union_chain(&[1, 4, 7]);
+    uf.union(2, 9);
+    uf.union(3, 5);
+    assert_eq!(uf.seeded_component(&[4, 9]), vec![1, 2, 4, 7, 9]);
+    assert_eq!(uf.seeded_component(&[7, 1]), vec![1, 4, 7]);
+    assert!(uf.seeded_component(&[]).is_empty());
+}
+
+#[test]
+fn test_components_by_size() {
+    let mut uf = UnionFind::new(8);
+    uf.union(6, 1);
+    uf.union_chain(&[0, 3, 5, 7]);
+    let comps = uf.components_by_size();
+    assert_eq!(comps.len(), uf.curr_len());
+    assert_eq!(comps[0], (7, vec![0, 3, 5, 7]));
+    assert_eq!(comps[1], (1, vec![1, 6]));
+    assert_eq!(comps[2], (2, vec![2]));
+    assert_eq!(comps[3], (4, vec![4]));
+}
+
+#[test]
+fn test_iter_components() {
+    let mut uf = UnionFind::<u32>::with_capacity(9);
+    uf.union_chain(&[8, 2, 5]);
+    uf.union(0, 6);
+    let mut nested = uf
+        .iter_components()
+        .map(|(r, members)| {
+            let members = members.collect::<Vec<_>>();
+            assert!(members.contains(&r));
+            members
+        })
+        .collect::<Vec<_>>();
+    let mut groups = uf.groups();
+    nested.sort();
+    groups.sort();
+    assert_eq!(nested, groups);
+}
+
+#[test]
+fn test_subset_same_partition() {
+    let mut a = UnionFind::new(10);
+    let mut b = UnionFind::<u32>::with_capacity(10);
+    a.union_chain(&[2, 3, 4]);
+    b.union_chain(&[4, 2, 3]);
+    a.union(0, 8);
+    b.union(1, 9);
+    assert!(a.subset_same_partition(&b, 2..8));
+    assert!(a.subset_same_partition(&b, 0..8));
+    assert!(!a.subset_same_partition(&b, 0..10));
+    b.union(5, 6);
+    assert!(!a.subset_same_partition(&b, 2..8));
+}
+
+#[test]
+fn test_join_all() {
+    let mut a = UnionFind::<u32>::with_capacity(9);
+    a.union(0, 1);
+    let mut b = UnionFind::<u32>::with_capacity(9);
+    b.union_chain(&[1, 2, 3]);
+    let mut c = UnionFind::<u32>::with_capacity(9);
+    c.union(6, 8);
+    let joined = UnionFind::join_all(&[&a, &b, &c]);
+    let mut expected = a.clone();
+    expected.union_with(&b);
+    expected.union_with(&c);
+    assert!(joined.same_partition(&expected));
+    assert_eq!(joined.curr_len(), 5);
+    assert_eq!(UnionFind::join_all(&[]).capacity(), 0);
+}
+
+#[test]
+fn test_merges_between() {
+    let mut prev = UnionFind::new(8);
+    prev.union(0, 1);
+    prev.union(2, 3);
+    let mut curr = prev.clone();
+    curr.union(1, 3);
+    assert_eq!(curr.merges_between(&prev), vec![(prev.get(0), prev.get(2))]);
+    assert!(prev.merges_between(&prev).is_empty());
+    curr.union_chain(&[4, 5, 6]);
+    assert_eq!(curr.merges_between(&prev).len(), 3);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_members_of() {
+    let mut uf = UnionFind::<u32>::with_capacity(8);
+    uf.union_chain(&[6, 1, 3]);
+    uf.union(0, 7);
+    assert_eq!(uf.members_of(3), vec![1, 3, 6]);
+    assert_eq!(uf.members_of(2), vec![2]);
+    let comps = uf.components();
+    assert_eq!(comps.len(), uf.curr_len());
+    for (&r, members) in &comps {
+        assert!(members.contains(&r));
+        assert_eq!(*members, uf.members_of(r));
+    }
+}
+
+#[test]
+fn test_find_component() {
+    let mut uf = UnionFind::new(10);
+    uf.union_chain(&[0, 4, 1]);
+    uf.union_chain(&[5, 6, 7, 8]);
+    let calls = core::cell::Cell::new(0);
+    let found = uf.find_component(|_, m| {
+        calls.set(calls.get() + 1);
+        m.len() >= 3
+    });
+    assert_eq!(found, Some((1, vec![0, 1, 4])));
+    assert_eq!(calls.get(), 1);
+    assert_eq!(uf.find_component(|_, m| m.len() > 4), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_refine_by() {
+    let mut uf = UnionFind::<u32>::with_capacity(8);
+    uf.union_chain(&[0, 1, 2, 3, 4, 5, 6, 7]);
+    uf.refine_by(|i| i % 2);
+    assert_eq!(uf.curr_len(), 2);
+    assert_eq!(uf.members_of(0), vec![0, 2, 4, 6]);
+    assert_eq!(uf.members_of(1), vec![1, 3, 5, 7]);
+    assert_eq!(uf.size_of(5), 4);
+
+    let mut uf = UnionFind::new(6);
+    uf.union(0, 1);
+    uf.union(2, 3);
+    uf.refine_by(|i| i < 3);
+    assert_eq!(uf.curr_len(), 5);
+    assert!(uf.same_set(0, 1));
+    assert!(!uf.same_set(2, 3));
+}
+
+#[test]
+fn test_compact_deleted() {
+    let mut uf = UnionFind::new(7);
+    uf.union_chain(&[0, 2, 4]);
+    uf.union(5, 6);
+    let mut deleted = vec![false; 7];
+    deleted[2] = true;
+    deleted[6] = true;
+    let (out, remap) = uf.compact_deleted(&deleted);
+    assert_eq!(
+        remap,
+        vec![Some(0), Some(1), None, Some(2), Some(3), Some(4), None]
+    );
+    assert_eq!(out.capacity(), 5);
+    assert_eq!(out.curr_len(), 4);
+    // 0 and 4 stay connected even though 2 is deleted.
+    assert_eq!(out.get(0), out.get(3));
+    assert!(out.is_root(1) && out.is_root(2) && out.is_root(4));
+}
+
+#[test]
+fn test_concat() {
+    let mut a = UnionFind::new(4);
+    a.union_chain(&[0, 2, 3]);
+    let mut b = UnionFind::<u32>::with_capacity(3);
+    b.union(2, 0);
+    let c = a.concat(&b);
+    assert_eq!(c.capacity(), 7);
+    assert_eq!(c.curr_len(), a.curr_len() + b.curr_len());
+    for i in 0..4 {
+        for j in 0..3 {
+            assert!(!c.connected(i, j + 4));
+        }
+    }
+    assert!(c.connected(0, 3) && !c.connected(0, 1));
+    assert!(c.connected(4, 6) && !c.connected(4, 5));
+}
+
+#[test]
+fn test_same_size_profile() {
+    let mut a = UnionFind::new(6);
+    a.union_chain(&[0, 1, 2]);
+    a.union(3, 4);
+    let mut b = UnionFind::new(6);
+    b.union_chain(&[5, 3, 1]);
+    b.union(0, 4);
+    assert!(a.same_size_profile(&b));
+    assert!(!a.same_partition(&b));
+    b.union(2, 0);
+    assert!(!a.same_size_profile(&b));
+}
+
+#[test]
+fn test_component_labels() {
+    let mut uf = UnionFind::new(3);
+    uf.union(0, 2);
+    assert_eq!(uf.component_labels(), vec![0, 1, 0]);
+
+    let mut uf = UnionFind::<u32>::with_capacity(6);
+    uf.union(1, 4);
+    uf.union(5, 0);
+    let labels = uf.component_labels();
+    assert_eq!(labels, vec![0, 1, 2, 3, 1, 0]);
+    let mut distinct = labels.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+    assert_eq!(distinct.len(), uf.curr_len());
+}
+
+#[test]
+fn test_partition() {
+    let mut uf = UnionFind::<u32>::with_capacity(64);
+    let mut state = 0x2545_f491_u64;
+    for _ in 0..40 {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+        let (a, b) = ((state >> 33) as usize % 64, (state >> 45) as usize % 64);
+        uf.union(a, b);
+    }
+    let parts = uf.partition();
+    assert_eq!(parts.len(), uf.curr_len());
+    assert_eq!(parts.iter().map(Vec::len).sum::<usize>(), uf.capacity());
+    let mut seen = [false; 64];
+    for part in &parts {
+        for &i in part {
+            assert!(!core::mem::replace(&mut seen[i], true));
+            assert!(uf.same_set(i, part[0]));
+        }
+    }
+}
+
+#[test]
+fn test_into_iter() {
+    let mut uf = UnionFind::<u32>::with_capacity(6);
+    uf.union_chain(&[0, 2, 4]);
+    uf.union(5, 1);
+    let before = uf.clone();
+    let roots = (&uf).into_iter().collect::<BTreeMap<_, _>>();
+    assert_eq!(uf, before);
+    assert_eq!(roots.len(), 6);
+    assert_eq!(roots[&0], roots[&2]);
+    assert_eq!(roots[&0], roots[&4]);
+    assert_eq!(roots[&1], roots[&5]);
+    assert_ne!(roots[&0], roots[&1]);
+    assert_eq!(roots[&3], 3);
+
+    let v = UnionFind::new(3);
+    let pairs = (&v).into_iter().collect::<Vec<_>>();
+    assert_eq!(pairs, vec![(0, 0), (1, 1), (2, 2)]);
+    assert!((&v).into_iter().all(|(i, r)| i == r));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_hash() {
+    use std::hash::{BuildHasher, RandomState};
+    let state = RandomState::new();
+    let mut a = UnionFind::<u32>::with_capacity(5);
+    let mut b = UnionFind::<u32>::with_capacity(5);
+    a.union_chain(&[0, 1, 2]);
+    b.union_chain(&[2, 1, 0]);
+    assert_ne!(a, b);
+    assert!(a.same_partition(&b));
+    assert_eq!(state.hash_one(&a), state.hash_one(&b));
+
+    let before = a.clone();
+    state.hash_one(&a);
+    assert_eq!(a, before);
+
+    b.union(3, 4);
+    assert_ne!(state.hash_one(&a), state.hash_one(&b));
+
+    // Keys are never queried through `&self` methods which compress, so they do not change.
+    #[allow(clippy::mutable_key_type)]
+    let mut memo = HashMap::new();
+    memo.insert(UnionFind::new(3), 1);
+    assert_eq!(memo.get(&UnionFind::new(3)), Some(&1));
+}
+
+#[test]
+fn test_same_partition() {
+    let mut a = UnionFind::new(4);
+    a.union(0, 1);
+    a.union(1, 2);
+    let mut b = UnionFind::new(4);
+    b.union(2, 0);
+    b.union(1, 0);
+    assert_ne!(a, b);
+    assert!(a.same_partition(&b));
+    assert_eq!(a.component_labels(), b.component_labels());
+
+    b.union(3, 0);
+    assert!(!a.same_partition(&b));
+    assert!(!a.same_partition(&UnionFind::new(5)));
+}
+
+#[test]
+fn test_labeling() {
+    let mut uf = UnionFind::<u32>::with_capacity(12);
+    uf.union_all(&[(3, 9), (9, 0), (5, 11), (7, 2), (2, 11)]);
+    let labels = uf.component_labels();
+    let labeling = uf.labeling();
+    assert!((0..12).all(|v| labeling.label_of(v) == labels[v]));
+    assert_eq!(labeling.label_of(0), 0);
+    assert_eq!(labeling.label_of(11), labeling.label_of(7));
+}
+
+#[test]
+fn test_component_bitset() {
+    let mut uf = UnionFind::new(130);
+    let members = [1, 63, 64, 100, 129];
+    uf.union_chain(&members);
+    uf.set(2, 3);
+    let bits = uf.component_bitset(100);
+    assert_eq!(bits.len(), 3);
+    let ones = bits.iter().map(|w| w.count_ones() as usize).sum::<usize>();
+    assert_eq!(ones, members.len());
+    for i in 0..130 {
+        let set = bits[i / 64] >> (i % 64) & 1 == 1;
+        assert_eq!(set, members.contains(&i));
+        assert_eq!(set, uf.same_set(i, 100));
+    }
+}
+
+#[test]
+fn test_ord_by_partition() {
+    let build = |pairs: &[(usize, usize)]| {
+        let mut uf = UnionFind::<u32>::with_capacity(4);
+        uf.union_all(pairs);
+        uf
+    };
+    let a = build(&[(0, 1)]);
+    let b = build(&[(1, 0)]);
+    let c = build(&[(2, 3)]);
+    let d = build(&[]);
+    assert_ne!(a, b);
+    assert!(a.same_partition(&b));
+    let mut all = [c.clone(), a.clone(), d.clone(), b, a.clone()];
+    all.sort();
+    // [0, 0, 1, 2] sorts before [0, 1, 2, 2] and [0, 1, 2, 3].
+    assert!(all[..3].iter().all(|uf| uf.same_partition(&a)));
+    assert_eq!(all[3], c);
+    assert_eq!(all[4], d);
+    assert!(
+        all.windows(2)
+            .all(|w| (w[0] == w[1]) == (w[0].cmp(&w[1]) == Ordering::Equal))
+    );
+}
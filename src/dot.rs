@@ -0,0 +1,43 @@
+use super::{UfIndex, UnionFind};
+use alloc::string::String;
+use core::fmt::Write;
+
+/// Writes a Graphviz digraph with an edge from each non-root to its parent, given the parent of
+/// each element. Roots are drawn as double circles.
+fn dot(parents: impl Iterator<Item = usize>) -> String {
+    let mut out = String::from("digraph {\n");
+    for (i, p) in parents.enumerate() {
+        if p == i {
+            writeln!(out, "  {i} [shape=doublecircle];").unwrap();
+        } else {
+            writeln!(out, "  {i} -> {p};").unwrap();
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+impl<T: UfIndex> UnionFind<T> {
+    /// Renders the current parent pointers, without compressing, as a Graphviz digraph.
+    pub fn to_dot(&self) -> String {
+        dot(self.ptrs.iter().map(|p| p.get().to_usize()))
+    }
+}
+
+#[test]
+fn test_to_dot() {
+    let mut uf = UnionFind::<u32>::with_capacity(4);
+    uf.set(0, 1);
+    uf.set(1, 2);
+    let dot = uf.to_dot();
+    assert!(dot.starts_with("digraph {\n"));
+    assert!(dot.contains("  0 -> 1;\n"));
+    assert!(dot.contains("  1 -> 2;\n"));
+    assert!(dot.contains("  2 [shape=doublecircle];\n"));
+    assert!(dot.contains("  3 [shape=doublecircle];\n"));
+    assert_eq!(dot.matches("->").count(), 2);
+    assert_eq!(
+        UnionFind::new(1).to_dot(),
+        "digraph {\n  0 [shape=doublecircle];\n}\n"
+    );
+}
@@ -0,0 +1,56 @@
+use super::{UnionFind, UnionFindOp};
+use alloc::vec::Vec;
+
+/// A union-find which tracks the minimum and maximum of a per-element value for each component.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtremaUnionFind {
+    uf: UnionFind<u32>,
+    /// Minimum value of each component, only valid at roots.
+    min: Vec<f64>,
+    /// Maximum value of each component, only valid at roots.
+    max: Vec<f64>,
+}
+
+impl ExtremaUnionFind {
+    /// Creates a set of singletons, where each element's min and max is its own value.
+    pub fn new(values: &[f64]) -> Self {
+        Self {
+            uf: UnionFind::<u32>::with_capacity(values.len()),
+            min: values.to_vec(),
+            max: values.to_vec(),
+        }
+    }
+    /// Unions the components of `a` and `b`, merging their extrema.
+    pub fn union_track_extrema(&mut self, a: usize, b: usize) {
+        let ra = self.uf.find(a);
+        let rb = self.uf.find(b);
+        if ra == rb {
+            return;
+        }
+        let min = self.min[ra].min(self.min[rb]);
+        let max = self.max[ra].max(self.max[rb]);
+        self.uf.union(ra, rb);
+        let root = self.uf.find(rb);
+        self.min[root] = min;
+        self.max[root] = max;
+    }
+    /// Returns the `(min, max)` of the component containing `v`.
+    pub fn extrema_of(&self, v: usize) -> (f64, f64) {
+        let r = self.uf.find(v);
+        (self.min[r], self.max[r])
+    }
+    /// The underlying union-find.
+    pub fn inner(&self) -> &UnionFind<u32> {
+        &self.uf
+    }
+}
+
+#[test]
+fn test_extrema() {
+    let mut uf = ExtremaUnionFind::new(&[3.0, 1.0, 5.0, 7.0]);
+    uf.union_track_extrema(0, 1);
+    uf.union_track_extrema(2, 1);
+    assert_eq!(uf.extrema_of(0), (1.0, 5.0));
+    assert_eq!(uf.extrema_of(2), (1.0, 5.0));
+    assert_eq!(uf.extrema_of(3), (7.0, 7.0));
+}
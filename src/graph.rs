@@ -0,0 +1,372 @@
+use super::{UfIndex, UnionFind, UnionFindOp};
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+impl<T: UfIndex> UnionFind<T> {
+    /// Checks whether adding the edge `a`-`b` would close a cycle, i.e. whether they are
+    /// already in the same component. Unlike [`UnionFindOp::same_set`], this never compresses.
+    pub fn creates_cycle(&self, a: usize, b: usize) -> bool {
+        self.get(a) == self.get(b)
+    }
+    /// Creates `size` singletons and unions the endpoints of each edge.
+    pub fn from_edges(size: usize, edges: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        let mut uf = Self::singletons(size);
+        for (a, b) in edges {
+            uf.union(a, b);
+        }
+        uf
+    }
+    /// Unions `v` and `to`, first growing to include both if either is out of range.
+    pub fn union_grow(&mut self, v: usize, to: usize) -> bool {
+        self.ensure_capacity(v.max(to));
+        self.set(v, to)
+    }
+}
+
+/// Unions each pair, growing to include any out of range index as in
+/// [`UnionFind::union_grow`].
+impl<T: UfIndex> Extend<(usize, usize)> for UnionFind<T> {
+    fn extend<I: IntoIterator<Item = (usize, usize)>>(&mut self, edges: I) {
+        for (a, b) in edges {
+            self.union_grow(a, b);
+        }
+    }
+}
+
+/// Builds a union-find whose capacity is one more than the largest index of any pair, or empty
+/// if there are no pairs.
+impl<T: UfIndex> FromIterator<(usize, usize)> for UnionFind<T> {
+    fn from_iter<I: IntoIterator<Item = (usize, usize)>>(edges: I) -> Self {
+        let edges = edges.into_iter().collect::<Vec<_>>();
+        let size = edges.iter().map(|&(a, b)| a.max(b) + 1).max().unwrap_or(0);
+        Self::from_edges(size, edges)
+    }
+}
+
+impl UnionFind<u32> {
+    /// Computes the 2-edge-connected components of a graph with `n` vertices, by unioning the
+    /// endpoints of every edge whose index is not contained in `bridges`.
+    /// The bridges must be computed elsewhere.
+    pub fn two_edge_connected(n: usize, edges: &[(u32, u32)], bridges: &[usize]) -> Self {
+        let mut is_bridge = vec![false; edges.len()];
+        for &b in bridges {
+            is_bridge[b] = true;
+        }
+        let mut uf = Self::with_capacity(n);
+        for (&(a, b), _) in edges.iter().zip(is_bridge).filter(|(_, br)| !br) {
+            uf.union(a as usize, b as usize);
+        }
+        uf
+    }
+    /// Processes `edges` in order, returning the index of the edge after which `a` and `b` are
+    /// first connected, or `None` if they never are.
+    pub fn first_connecting_edge(
+        n: usize,
+        edges: &[(u32, u32)],
+        a: usize,
+        b: usize,
+    ) -> Option<usize> {
+        let mut uf = Self::with_capacity(n);
+        edges.iter().position(|&(u, v)| {
+            uf.union(u as usize, v as usize);
+            uf.same_set(a, b)
+        })
+    }
+    /// Lazily runs Kruskal's algorithm over `(a, b, weight)` edges already sorted by weight,
+    /// yielding the index of each edge which joins two components, and unioning them as the
+    /// iterator is driven. Stopping early leaves only the yielded edges applied.
+    pub fn kruskal_iter<'a>(
+        &'a mut self,
+        sorted_edges: &'a [(u32, u32, u32)],
+    ) -> impl Iterator<Item = usize> + 'a {
+        sorted_edges
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, &(a, b, _))| self.set(a as usize, b as usize).then_some(i))
+    }
+    /// Builds a union-find from a stream of edges, growing as new vertices are encountered.
+    pub fn from_edge_iter<I: IntoIterator<Item = (u32, u32)>>(iter: I) -> Self {
+        let mut uf = Self::with_capacity(0);
+        for (a, b) in iter {
+            uf.union_grow(a as usize, b as usize);
+        }
+        uf
+    }
+}
+
+impl<T: Copy + Eq> UnionFind<T>
+where
+    Self: UnionFindOp,
+{
+    /// Number of edges whose endpoints lie in different components.
+    pub fn cut_size(&self, edges: &[(u32, u32)]) -> usize {
+        edges.len() - self.self_loops_after_contraction(edges)
+    }
+    /// Number of edges whose endpoints lie in the same component, and so would become self-loops
+    /// if each component was contracted to a single vertex.
+    pub fn self_loops_after_contraction(&self, edges: &[(u32, u32)]) -> usize {
+        edges
+            .iter()
+            .filter(|&&(a, b)| self.same_set(a as usize, b as usize))
+            .count()
+    }
+    /// Returns an edge from each non-root element to its root, which is a minimal set of edges
+    /// reproducing this partition.
+    pub fn closure_star_edges(&self) -> Vec<(u32, u32)> {
+        (0..self.capacity())
+            .filter_map(|i| {
+                let r = self.find(i);
+                (r != i).then_some((i as u32, r as u32))
+            })
+            .collect()
+    }
+    /// Returns one edge between the roots of each pair of consecutive components, which is a
+    /// minimal set of edges that would merge every component into one.
+    pub fn connecting_edges(&self) -> Vec<(usize, usize)> {
+        let roots = (0..self.capacity())
+            .filter(|&i| self.find(i) == i)
+            .collect::<Vec<_>>();
+        roots.windows(2).map(|w| (w[0], w[1])).collect()
+    }
+    /// Hashes the size and sorted internal edges of each component, with every member relabeled
+    /// by its rank within the component, and returns the hash of each root. Components with the
+    /// same edges up to this relabeling have equal signatures.
+    #[cfg(feature = "std")]
+    pub fn component_signatures(&self, edges: &[(u32, u32)]) -> HashMap<usize, u64> {
+        let (offsets, members) = self.csr();
+        let mut local = vec![0u32; self.capacity()];
+        for w in offsets.windows(2) {
+            for (l, &m) in members[w[0]..w[1]].iter().enumerate() {
+                local[m] = l as u32;
+            }
+        }
+        let mut internal = HashMap::<usize, Vec<(u32, u32)>>::new();
+        for &(a, b) in edges {
+            let r = self.find(a as usize);
+            if r != self.find(b as usize) {
+                continue;
+            }
+            let (la, lb) = (local[a as usize], local[b as usize]);
+            internal
+                .entry(r)
+                .or_default()
+                .push((la.min(lb), la.max(lb)));
+        }
+        offsets
+            .windows(2)
+            .map(|w| {
+                let r = self.find(members[w[0]]);
+                let mut es = internal.remove(&r).unwrap_or_default();
+                es.sort_unstable();
+                let mut h = DefaultHasher::new();
+                (w[1] - w[0]).hash(&mut h);
+                es.hash(&mut h);
+                (r, h.finish())
+            })
+            .collect()
+    }
+    /// Checks whether this partition is exactly the connected components of `edges`, i.e. no
+    /// edge crosses two components, and every component is connected by `edges`.
+    pub fn is_valid_cc(&self, edges: &[(u32, u32)]) -> bool {
+        if self.cut_size(edges) != 0 {
+            return false;
+        }
+        // every edge is within a component, so the components of `edges` refine this partition.
+        let mut cc = UnionFind::<u32>::with_capacity(self.capacity());
+        for &(a, b) in edges {
+            cc.union(a as usize, b as usize);
+        }
+        let roots = (0..self.capacity()).filter(|&i| self.find(i) == i).count();
+        cc.curr_len() == roots
+    }
+}
+
+#[test]
+fn test_two_edge_connected() {
+    // two triangles joined by the bridge 2-3.
+    let edges = [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 3)];
+    let uf = UnionFind::two_edge_connected(6, &edges, &[3]);
+    assert_eq!(uf.curr_len(), 2);
+    assert_eq!(uf.get(0), uf.get(1));
+    assert_eq!(uf.get(1), uf.get(2));
+    assert_eq!(uf.get(3), uf.get(4));
+    assert_eq!(uf.get(4), uf.get(5));
+    assert_ne!(uf.get(2), uf.get(3));
+}
+
+#[test]
+fn test_self_loops_after_contraction() {
+    let mut uf = UnionFind::new(6);
+    uf.union(0, 1);
+    uf.union(1, 2);
+    uf.union(4, 5);
+    let edges = [(0, 1), (0, 2), (2, 3), (3, 4), (5, 4), (1, 5)];
+    let loops = uf.self_loops_after_contraction(&edges);
+    assert_eq!(loops, 3);
+    assert_eq!(loops + uf.cut_size(&edges), edges.len());
+}
+
+#[test]
+fn test_is_valid_cc() {
+    let edges = [(0, 1), (1, 2), (3, 4)];
+    let mut uf = UnionFind::new(6);
+    uf.union(2, 0);
+    uf.union(1, 0);
+    assert!(!uf.is_valid_cc(&edges));
+    uf.union(4, 3);
+    assert!(uf.is_valid_cc(&edges));
+    uf.union(4, 5);
+    assert!(!uf.is_valid_cc(&edges));
+}
+
+#[test]
+fn test_from_edge_iter() {
+    let uf = UnionFind::from_edge_iter((0..10).map(|i| (i, i % 3)));
+    assert_eq!(uf.capacity(), 10);
+    assert_eq!(uf.curr_len(), 3);
+    assert_eq!(uf.get(9), uf.get(0));
+    assert_eq!(uf.get(7), uf.get(1));
+    assert_ne!(uf.get(5), uf.get(7));
+}
+
+#[test]
+fn test_first_connecting_edge() {
+    let edges = [(0, 1), (2, 3), (4, 5), (1, 2), (3, 4)];
+    assert_eq!(UnionFind::first_connecting_edge(6, &edges, 0, 3), Some(3));
+    assert_eq!(UnionFind::first_connecting_edge(6, &edges, 0, 5), Some(4));
+    assert_eq!(UnionFind::first_connecting_edge(7, &edges, 0, 6), None);
+}
+
+#[test]
+fn test_closure_star_edges() {
+    let mut uf = UnionFind::<u32>::with_capacity(8);
+    uf.union_chain(&[0, 3, 5]);
+    uf.union(6, 1);
+    let edges = uf.closure_star_edges();
+    assert_eq!(edges.len(), uf.capacity() - uf.curr_len());
+    let mut rebuilt = UnionFind::<u32>::with_capacity(8);
+    for &(a, b) in &edges {
+        rebuilt.union(a as usize, b as usize);
+    }
+    assert!(rebuilt.same_partition(&uf));
+}
+
+#[test]
+fn test_creates_cycle() {
+    let mut uf = UnionFind::new(6);
+    uf.union_chain(&[0, 1, 2]);
+    uf.union(4, 5);
+    assert!(uf.creates_cycle(0, 2));
+    assert!(uf.creates_cycle(5, 4));
+    assert!(!uf.creates_cycle(2, 3));
+    assert!(!uf.creates_cycle(1, 4));
+    let mut uf = UnionFind::<u32>::with_capacity(4);
+    uf.union(0, 3);
+    assert!(uf.creates_cycle(3, 0));
+    assert!(!uf.creates_cycle(1, 2));
+}
+
+#[test]
+fn test_kruskal_iter() {
+    let edges = [
+        (0, 1, 1),
+        (1, 2, 2),
+        (0, 2, 3),
+        (3, 4, 4),
+        (2, 3, 5),
+        (4, 1, 6),
+    ];
+    let mut eager = UnionFind::<u32>::with_capacity(6);
+    let expected = (0..edges.len())
+        .filter(|&i| {
+            let (a, b, _) = edges[i];
+            let cycle = eager.creates_cycle(a as usize, b as usize);
+            eager.union(a as usize, b as usize);
+            !cycle
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(expected, vec![0, 1, 3, 4]);
+
+    let mut uf = UnionFind::<u32>::with_capacity(6);
+    assert_eq!(uf.kruskal_iter(&edges).collect::<Vec<_>>(), expected);
+    assert!(uf.same_partition(&eager));
+
+    let mut uf = UnionFind::<u32>::with_capacity(6);
+    assert_eq!(
+        uf.kruskal_iter(&edges).take(2).collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+    assert_eq!(uf.curr_len(), 4);
+}
+
+#[test]
+fn test_connecting_edges() {
+    let mut uf = UnionFind::new(8);
+    uf.union_chain(&[0, 2, 4]);
+    uf.union(5, 7);
+    let edges = uf.connecting_edges();
+    assert_eq!(edges.len(), uf.curr_len() - 1);
+    for (a, b) in edges {
+        uf.union(a, b);
+    }
+    assert_eq!(uf.curr_len(), 1);
+    assert!(UnionFind::new(0).connecting_edges().is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_component_signatures() {
+    // two paths of length 3 with the same shape, and a triangle.
+    let edges = [(0, 1), (1, 2), (4, 5), (5, 6), (7, 8), (8, 9), (9, 7)];
+    let mut uf = UnionFind::<u32>::with_capacity(10);
+    for &(a, b) in &edges {
+        uf.union(a as usize, b as usize);
+    }
+    let sigs = uf.component_signatures(&edges);
+    assert_eq!(sigs.len(), uf.curr_len());
+    assert_eq!(sigs[&uf.get(0)], sigs[&uf.get(4)]);
+    assert_ne!(sigs[&uf.get(0)], sigs[&uf.get(7)]);
+    assert_ne!(sigs[&uf.get(0)], sigs[&uf.get(3)]);
+}
+
+#[test]
+fn test_from_edges() {
+    let edges = [(0, 1), (1, 2), (3, 4)];
+    let uf = UnionFind::<usize>::from_edges(5, edges);
+    assert_eq!(uf.curr_len(), 2);
+    assert!(uf.creates_cycle(0, 2));
+    assert!(uf.creates_cycle(3, 4));
+    assert!(!uf.creates_cycle(2, 3));
+
+    let uf = UnionFind::<u32>::from_edges(5, edges);
+    assert_eq!(uf.curr_len(), 2);
+    assert_eq!(uf.get(1), uf.get(2));
+    assert_ne!(uf.get(0), uf.get(4));
+}
+
+#[test]
+fn test_extend() {
+    let mut uf = UnionFind::new(5);
+    uf.extend([(0, 1), (3, 4)]);
+    assert_eq!(uf.capacity(), 5);
+    assert_eq!(uf.curr_len(), 3);
+
+    uf.extend([(4, 7), (1, 2)]);
+    assert_eq!(uf.capacity(), 8);
+    assert_eq!(uf.curr_len(), 4);
+    assert!(uf.creates_cycle(3, 7));
+    assert!(uf.is_root(uf.get(5)) && uf.get(5) == 5);
+}
+
+#[test]
+fn test_from_iter() {
+    let uf: UnionFind = [(0, 1), (5, 2)].into_iter().collect();
+    assert_eq!(uf.capacity(), 6);
+    assert_eq!(uf.curr_len(), 4);
+    assert!(uf.creates_cycle(2, 5));
+    let uf: UnionFind<u32> = core::iter::empty().collect();
+    assert_eq!(uf.capacity(), 0);
+}
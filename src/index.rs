@@ -0,0 +1,35 @@
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An unsigned integer type which can back a [`UnionFind`](crate::UnionFind). Smaller types
+/// use less memory, but limit the number of elements.
+pub trait UfIndex: Copy + Eq + sealed::Sealed {
+    /// Exclusive upper bound on the number of elements.
+    const MAX: usize;
+    fn from_usize(v: usize) -> Self;
+    fn to_usize(self) -> usize;
+}
+
+macro_rules! impl_index {
+    ($($t: ty),*) => {$(
+        impl sealed::Sealed for $t {}
+        impl UfIndex for $t {
+            const MAX: usize = if <$t>::BITS >= usize::BITS {
+                usize::MAX
+            } else {
+                <$t>::MAX as usize
+            };
+            #[inline]
+            fn from_usize(v: usize) -> Self {
+                v as $t
+            }
+            #[inline]
+            fn to_usize(self) -> usize {
+                self as usize
+            }
+        }
+    )*};
+}
+
+impl_index!(u8, u16, u32, u64, usize);
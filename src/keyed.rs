@@ -0,0 +1,76 @@
+use super::{UnionFind, UnionFindOp};
+use core::hash::Hash;
+use std::collections::HashMap;
+
+/// A union-find over arbitrary keys, which are assigned dense indices as they are first seen.
+#[derive(Debug, Clone)]
+pub struct KeyedUnionFind<K: Hash + Eq + Clone> {
+    indices: HashMap<K, usize>,
+    uf: UnionFind<usize>,
+}
+
+impl<K: Hash + Eq + Clone> KeyedUnionFind<K> {
+    pub fn new() -> Self {
+        Self {
+            indices: HashMap::new(),
+            uf: UnionFind::new(0),
+        }
+    }
+    /// The index of `k`, adding it as a new singleton if it has not been seen before.
+    pub fn index_of(&mut self, k: &K) -> usize {
+        if let Some(&i) = self.indices.get(k) {
+            return i;
+        }
+        let i = self.uf.extend_by(1).start;
+        self.indices.insert(k.clone(), i);
+        i
+    }
+    /// Finds the index of the root of `k`'s component, interning `k` if it is new.
+    pub fn find(&mut self, k: &K) -> usize {
+        let i = self.index_of(k);
+        self.uf.find(i)
+    }
+    /// Unions the components of `a` and `b`, returning whether they were previously distinct.
+    pub fn union(&mut self, a: &K, b: &K) -> bool {
+        let a = self.index_of(a);
+        let b = self.index_of(b);
+        self.uf.set(a, b)
+    }
+    /// Checks whether `a` and `b` are in the same component. Keys which have not been seen are
+    /// singletons.
+    pub fn same_set(&self, a: &K, b: &K) -> bool {
+        match (self.indices.get(a), self.indices.get(b)) {
+            (Some(&a), Some(&b)) => self.uf.same_set(a, b),
+            _ => a == b,
+        }
+    }
+    /// Number of distinct keys seen.
+    pub fn num_keys(&self) -> usize {
+        self.indices.len()
+    }
+    /// The underlying union-find, indexed by [`Self::index_of`].
+    pub fn inner(&self) -> &UnionFind<usize> {
+        &self.uf
+    }
+}
+
+impl<K: Hash + Eq + Clone> Default for KeyedUnionFind<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[test]
+fn test_keyed() {
+    let mut uf = KeyedUnionFind::new();
+    assert!(uf.union(&"a.com", &"b.com"));
+    assert!(uf.union(&"b.com", &"c.com"));
+    assert!(!uf.union(&"c.com", &"a.com"));
+    assert!(uf.same_set(&"a.com", &"c.com"));
+    assert!(!uf.same_set(&"a.com", &"d.com"));
+    assert!(uf.same_set(&"d.com", &"d.com"));
+    assert_eq!(uf.find(&"a.com"), uf.find(&"c.com"));
+    assert_ne!(uf.find(&"d.com"), uf.find(&"a.com"));
+    assert_eq!(uf.num_keys(), 4);
+    assert_eq!(uf.inner().curr_len(), 2);
+}
@@ -1,171 +1,591 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(any(feature = "alloc", test))]
+extern crate alloc;
+
+#[cfg(feature = "unchecked")]
+macro_rules! idx {
+    ($s: expr, $vi: expr) => {
+        unsafe { $s.get_unchecked($vi) }
+    };
+}
+
+#[cfg(not(feature = "unchecked"))]
+macro_rules! idx {
+    ($s: expr, $vi: expr) => {{ &$s[$vi] }};
+}
+
+#[cfg(all(feature = "fuzzing", any(feature = "alloc", test)))]
+macro_rules! checked {
+    ($a: expr, -, $b: expr, $op: expr) => {
+        ($a).checked_sub($b).expect(concat!("underflow in ", $op))
+    };
+    ($a: expr, +, $b: expr, $op: expr) => {
+        ($a).checked_add($b).expect(concat!("overflow in ", $op))
+    };
+}
+
+#[cfg(all(not(feature = "fuzzing"), any(feature = "alloc", test)))]
+macro_rules! checked {
+    ($a: expr, -, $b: expr, $op: expr) => {
+        $a - $b
+    };
+    ($a: expr, +, $b: expr, $op: expr) => {
+        $a + $b
+    };
+}
+
+mod array;
+#[cfg(any(feature = "alloc", test))]
 mod atomic;
+#[cfg(any(feature = "alloc", test))]
+mod build;
+#[cfg(any(feature = "alloc", test))]
+mod components;
+#[cfg(feature = "dot")]
+mod dot;
+#[cfg(any(feature = "alloc", test))]
+mod extrema;
+#[cfg(any(feature = "alloc", test))]
+mod graph;
+mod index;
+#[cfg(feature = "std")]
+mod keyed;
+#[cfg(feature = "std")]
+mod metrics;
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(any(feature = "alloc", test))]
+mod payload;
+#[cfg(feature = "petgraph-compat")]
+mod petgraph;
+#[cfg(any(feature = "alloc", test))]
+mod rollback;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(any(feature = "alloc", test))]
+mod sized;
+mod slice;
+#[cfg(any(feature = "alloc", test))]
+mod snapshot;
+#[cfg(any(feature = "alloc", test))]
+mod trace;
+#[cfg(any(feature = "alloc", test))]
+mod weighted;
+pub use array::ArrayUnionFind;
+#[cfg(any(feature = "alloc", test))]
 pub use atomic::UnionFind as AtomicUnionFind;
+#[cfg(any(feature = "alloc", test))]
+pub use build::{BuildError, CapacityError};
+#[cfg(any(feature = "alloc", test))]
+pub use components::{ComponentMembers, ComponentsIter, ElementRoots, Labeling, Roots};
+#[cfg(any(feature = "alloc", test))]
+pub use extrema::ExtremaUnionFind;
+pub use index::UfIndex;
+#[cfg(feature = "std")]
+pub use keyed::KeyedUnionFind;
+#[cfg(any(feature = "alloc", test))]
+pub use payload::PayloadUnionFind;
+#[cfg(feature = "petgraph-compat")]
+pub use petgraph::PetgraphUnionFind;
+#[cfg(any(feature = "alloc", test))]
+pub use rollback::RollbackUnionFind;
+#[cfg(any(feature = "alloc", test))]
+pub use sized::{CapExceeded, SizedUnionFind};
+pub use slice::SliceUnionFind;
+#[cfg(any(feature = "alloc", test))]
+pub use snapshot::Snapshot;
+#[cfg(any(feature = "alloc", test))]
+pub use trace::TracedUnionFind;
+#[cfg(any(feature = "alloc", test))]
+pub use weighted::WeightedUnionFind;
 
+#[cfg(any(feature = "alloc", test))]
+use alloc::collections::TryReserveError;
+#[cfg(any(feature = "alloc", test))]
+use alloc::{vec, vec::Vec};
+#[cfg(any(feature = "alloc", test))]
 use core::cell::Cell;
+#[cfg(any(feature = "alloc", test))]
 use core::ops::Range;
 
+#[cfg(any(feature = "alloc", test))]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UnionFind<T: Copy + Eq = usize> {
     ptrs: Vec<Cell<T>>,
+    /// Number of elements in each component, only valid at roots.
+    sizes: Vec<T>,
 
-    len: usize,
+    /// Number of components, stored as `T` since it never exceeds the number of elements.
+    len: T,
+    /// Whether `find` uses path halving rather than only compressing the queried element.
+    halve: bool,
+    /// Whether `set` adds singletons for out of bounds elements rather than panicking.
+    growable: bool,
 }
 
 pub trait UnionFindOp {
     fn find(&self, v: usize) -> usize;
-    fn union(&mut self, v: usize, to: usize);
+    /// Finds the root of `v` without modifying any pointers, so repeated calls on deep trees
+    /// are slower than [`Self::find`]. The default forwards to `find`, so implementations which
+    /// compress should override it.
+    fn find_no_compress(&self, v: usize) -> usize {
+        self.find(v)
+    }
+    /// Unions the components of `v` and `to`, returning whether they were previously distinct.
+    fn union(&mut self, v: usize, to: usize) -> bool;
+    /// Checks whether `v` is the root of its component, returning `false` if `v` is out of range.
+    /// The default resolves the full path without compressing, so implementations should
+    /// override it with a single parent check.
     fn is_root(&self, v: usize) -> bool {
-        self.find(v) == v
+        self.find_no_compress(v) == v
     }
+    /// Number of disjoint sets, which is at most [`Self::capacity`].
     fn len(&self) -> usize;
+    /// Number of elements, regardless of how they are grouped.
     fn capacity(&self) -> usize;
+    /// Whether there are no elements, and therefore also no sets.
+    fn is_empty(&self) -> bool {
+        self.capacity() == 0
+    }
+
+    /// Checks whether `a` and `b` are in the same component.
+    fn same_set(&self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+    /// Fills `out[i]` with whether both elements of `pairs[i]` are in the same component.
+    fn same_set_many(&self, pairs: &[(usize, usize)], out: &mut [bool]) {
+        assert_eq!(pairs.len(), out.len());
+        for (&(a, b), o) in pairs.iter().zip(out.iter_mut()) {
+            *o = self.same_set(a, b);
+        }
+    }
+
+    /// Unions each consecutive pair of `chain`, returning the final root, or `None` if there
+    /// are fewer than two elements.
+    fn union_chain(&mut self, chain: &[usize]) -> Option<usize> {
+        if chain.len() < 2 {
+            return None;
+        }
+        for w in chain.windows(2) {
+            self.union(w[0], w[1]);
+        }
+        Some(self.find(chain[chain.len() - 1]))
+    }
+    /// Unions each run of `(start, len)` into a single component. Runs must be in bounds and
+    /// must not overlap.
+    #[cfg(any(feature = "alloc", test))]
+    fn union_runs(&mut self, runs: &[(usize, usize)]) {
+        let mut sorted = runs.to_vec();
+        sorted.sort_unstable();
+        let mut end = 0;
+        for &(start, len) in &sorted {
+            assert!(
+                start >= end,
+                "Run starting at {start} overlaps previous run"
+            );
+            end = start + len;
+            assert!(
+                end <= self.capacity(),
+                "Run {start}..{end} is out of bounds"
+            );
+        }
+        for &(start, len) in runs {
+            for i in start + 1..start + len {
+                self.union(i, start);
+            }
+        }
+    }
+    /// Unions every index in `lo..=hi` of each interval `(lo, hi)` into a single component.
+    /// Unlike [`Self::union_runs`], intervals may overlap, in which case their components merge.
+    fn union_intervals(&mut self, intervals: &[(usize, usize)]) {
+        for &(lo, hi) in intervals {
+            assert!(lo <= hi, "Interval {lo}..={hi} is empty");
+            assert!(
+                hi < self.capacity(),
+                "Interval {lo}..={hi} is out of bounds"
+            );
+        }
+        for &(lo, hi) in intervals {
+            for i in lo + 1..=hi {
+                self.union(i, lo);
+            }
+        }
+    }
+    /// Performs `count` unions of uniformly random pairs, returning the number of merges.
+    #[cfg(feature = "rand")]
+    fn random_unions<R: rand::Rng>(&mut self, count: usize, rng: &mut R) -> usize
+    where
+        Self: Sized,
+    {
+        use rand::RngExt;
+        let n = self.capacity();
+        let before = self.len();
+        for _ in 0..count {
+            self.union(rng.random_range(0..n), rng.random_range(0..n));
+        }
+        before - self.len()
+    }
 }
 
+#[cfg(any(feature = "alloc", test))]
 impl<T: Copy + Eq> UnionFind<T> {
+    /// Selects whether `find` uses path halving, see `get_halve`, instead of `get_compress`.
+    pub fn with_path_halving(mut self, halve: bool) -> Self {
+        self.halve = halve;
+        self
+    }
+    /// Selects whether [`Self::set`] grows to fit out of bounds elements, adding singletons up
+    /// to the larger of them, instead of panicking. Finds still panic when out of bounds.
+    pub fn with_growth(mut self, growable: bool) -> Self {
+        self.growable = growable;
+        self
+    }
     #[inline]
     pub fn capacity(&self) -> usize {
         self.ptrs.len()
     }
+    /// Number of disjoint sets, the same as [`UnionFindOp::len`].
     #[inline]
-    pub fn curr_len(&self) -> usize {
-        self.len
+    pub fn curr_len(&self) -> usize
+    where
+        T: UfIndex,
+    {
+        self.len.to_usize()
     }
 }
 
-#[cfg(feature = "unchecked")]
-macro_rules! idx {
-    ($s: expr, $vi: expr) => {
-        unsafe { $s.get_unchecked($vi) }
-    };
-}
-
-#[cfg(not(feature = "unchecked"))]
-macro_rules! idx {
-    ($s: expr, $vi: expr) => {{ &$s[$vi] }};
+#[cfg(any(feature = "alloc", test))]
+impl<T: Copy + Eq> UnionFind<T>
+where
+    Self: UnionFindOp,
+{
+    /// Recomputes the number of components from the number of roots, returning whether the
+    /// cached count was out of sync.
+    pub fn repair_len(&mut self) -> bool
+    where
+        T: UfIndex,
+    {
+        let roots = (0..self.capacity()).filter(|&i| self.find(i) == i).count();
+        let changed = roots != self.len();
+        self.len = T::from_usize(roots);
+        changed
+    }
+    /// Unions `v` and `to`, keeping whichever root has the smaller `id` as the representative.
+    /// If all unions go through this method, each root will be the element with the smallest
+    /// `id` in its component. Returns whether a merge occurred.
+    pub fn union_by_external<F: Fn(usize) -> u64>(&mut self, v: usize, to: usize, id: F) -> bool {
+        let rv = self.find(v);
+        let rt = self.find(to);
+        if rv == rt {
+            return false;
+        }
+        if id(rv) < id(rt) {
+            self.union(rt, rv);
+        } else {
+            self.union(rv, rt);
+        }
+        true
+    }
 }
 
 /// A subset of another UnionFind. Note that all values passed should use values starting from
 /// 0, not those values from the original.
+#[cfg(any(feature = "alloc", test))]
 #[derive(Debug, PartialEq, Eq)]
 pub struct BorrowedUnionFind<'a, T: Copy + Eq = usize> {
     /// Slice of cells of original union find
     ptrs: &'a mut [Cell<T>],
+    /// Slice of component sizes of original union find
+    sizes: &'a mut [T],
     /// mutable reference to original len
-    len: &'a mut usize,
+    len: &'a mut T,
 
     own_len: usize,
     /// The range within the original UnionFind
     r: Range<usize>,
 }
 
+#[cfg(any(feature = "alloc", test))]
 impl<T: Copy + Eq> BorrowedUnionFind<'_, T> {
     #[inline]
     pub fn capacity(&self) -> usize {
         self.ptrs.len()
     }
+    /// Number of disjoint sets within this subset, the same as [`UnionFindOp::len`].
     #[inline]
     pub fn curr_len(&self) -> usize {
         self.own_len
     }
 }
 
-impl UnionFind<usize> {
+/// Summary of the depths of every element in a union-find's forest, where roots have depth 0.
+#[cfg(any(feature = "alloc", test))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepthStats {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    /// Number of elements which do not point directly at their root.
+    pub uncompressed: usize,
+}
+
+#[cfg(any(feature = "alloc", test))]
+impl<T: UfIndex> Default for UnionFind<T> {
+    fn default() -> Self {
+        Self::singletons(0)
+    }
+}
+
+#[cfg(any(feature = "alloc", test))]
+impl<T: UfIndex> UnionFind<T> {
+    /// Creates `len` singletons, the same as [`Self::singletons`].
     #[inline]
-    pub fn new(size: usize) -> Self {
-        let ptrs = vec![Cell::new(0); size];
-        for (i, ptr) in ptrs.iter().enumerate() {
-            ptr.set(i);
+    pub fn with_capacity(len: usize) -> Self {
+        Self::singletons(len)
+    }
+    /// Creates `len` singletons backed by any index type.
+    #[inline]
+    pub fn singletons(len: usize) -> Self {
+        assert!(
+            len < T::MAX,
+            "UnionFind<{}> will overflow",
+            core::any::type_name::<T>()
+        );
+        Self {
+            ptrs: (0..len).map(|i| Cell::new(T::from_usize(i))).collect(),
+            sizes: vec![T::from_usize(1); len],
+            len: T::from_usize(len),
+            halve: false,
+            growable: false,
         }
-        Self { ptrs, len: size }
     }
     #[inline]
     pub fn get(&self, mut v: usize) -> usize {
-        while let n = idx!(self.ptrs, v).get()
+        while let n = idx!(self.ptrs, v).get().to_usize()
             && n != v
         {
             v = n;
         }
         v
     }
+    /// Same as [`Self::get`], but follows two pointers per iteration, so there is one branch
+    /// for every two hops. Finds are bound by the latency of each dependent load, so this is
+    /// only a few percent faster on long chains, see `bench_get`.
+    #[inline]
+    pub fn get_unrolled(&self, mut v: usize) -> usize {
+        loop {
+            let p = idx!(self.ptrs, v).get().to_usize();
+            let gp = idx!(self.ptrs, p).get().to_usize();
+            if gp == p {
+                return p;
+            }
+            v = gp;
+        }
+    }
     pub fn get_compress(&self, v: usize) -> usize {
         let dst = self.get(v);
-        idx!(self.ptrs, v).set(dst);
+        idx!(self.ptrs, v).set(T::from_usize(dst));
         dst
     }
-    pub fn set(&mut self, v: usize, to: usize) {
-        assert!(v <= self.ptrs.len());
-        assert!(to <= self.ptrs.len());
-        let root_to = self.get_compress(to);
-        let root_v = self.get_compress(v);
-        if root_v != root_to {
-            idx!(self.ptrs, root_v).set(root_to);
-            self.len -= 1;
-        }
-    }
-    /// Checks if a vertex is itself the root of a tree
-    pub fn is_root(&self, v: usize) -> bool {
-        self.ptrs.get(v).map(|p| p.get() == v).unwrap_or(false)
+    /// Finds the root of `v` without any extra space, by halving the path to it. Equivalent to
+    /// [`Self::get_halve`].
+    #[inline]
+    pub fn find_no_alloc(&self, v: usize) -> usize {
+        self.get_halve(v)
     }
-    pub fn compress(&mut self) {
-        for i in 0..self.ptrs.len() {
-            // compress it to last item always to flatten pointer chains.
-            let terminal = self.get(i);
-            if terminal != i {
-                self.set(i, terminal);
+    /// Finds the root of `v` in a single pass, pointing every other node on the path at its
+    /// grandparent.
+    pub fn get_halve(&self, mut v: usize) -> usize {
+        loop {
+            let p = idx!(self.ptrs, v).get().to_usize();
+            if p == v {
+                return v;
             }
+            let gp = idx!(self.ptrs, p).get();
+            idx!(self.ptrs, v).set(gp);
+            v = gp.to_usize();
         }
     }
-    pub fn extend_by(&mut self, n: usize) {
-        let l = self.ptrs.len();
-        for i in 0..n {
-            self.ptrs.push(Cell::new(l + i));
-        }
-        self.len += n;
+    /// Unions `v` and `to` as in [`Self::set`], but always keeps the smaller root as the parent
+    /// regardless of argument order. If every union goes through this, each root is the
+    /// smallest element of its component, at the cost of an extra comparison per union.
+    pub fn set_by_min(&mut self, v: usize, to: usize) -> bool {
+        let root_v = self.get_compress(v);
+        let root_to = self.get_compress(to);
+        self.set(root_v.max(root_to), root_v.min(root_to))
     }
-}
-
-impl UnionFind<u32> {
-    #[inline]
-    pub fn new_u32(len: usize) -> Self {
-        assert!(len < u32::MAX as usize, "UnionFind<u32> will overflow");
-        let ptrs = vec![Cell::new(0); len];
-        for (i, ptr) in ptrs.iter().enumerate() {
-            ptr.set(i as u32);
+    /// Unions each pair in order, returning how many merged two distinct components.
+    pub fn union_all(&mut self, pairs: &[(usize, usize)]) -> usize {
+        pairs.iter().filter(|&&(a, b)| self.set(a, b)).count()
+    }
+    /// Merges all members of each group into the component of its first member, returning how
+    /// many unions merged two distinct components.
+    pub fn merge_groups<G: IntoIterator<Item = usize>>(
+        &mut self,
+        groups: impl IntoIterator<Item = G>,
+    ) -> usize {
+        let mut merges = 0;
+        for g in groups {
+            let mut g = g.into_iter();
+            let Some(first) = g.next() else { continue };
+            merges += g.filter(|&v| self.set(v, first)).count();
         }
-        Self { ptrs, len }
+        merges
     }
-    #[inline]
-    pub fn get(&self, mut v: usize) -> usize {
-        while let n = idx!(self.ptrs, v).get() as usize
-            && n != v
+    /// Number of components there would be after unioning `pairs`, without modifying `self`.
+    /// The unions are simulated on a scratch copy.
+    pub fn components_count_after(&self, pairs: &[(usize, usize)]) -> usize {
+        self.len.to_usize() - self.clone().union_all(pairs)
+    }
+    /// Finds the root of `v`, compressing it, or `None` if `v` is out of bounds. Always bounds
+    /// checks, even with the `unchecked` feature.
+    pub fn try_find(&self, v: usize) -> Option<usize> {
+        let mut root = v;
+        while let p = self.ptrs.get(root)?.get().to_usize()
+            && p != root
         {
-            v = n;
+            root = p;
         }
-        v
+        self.ptrs[v].set(T::from_usize(root));
+        Some(root)
     }
-    pub fn get_compress(&self, v: usize) -> usize {
-        let dst = self.get(v);
-        idx!(self.ptrs, v).set(dst as u32);
-        dst
+    /// Unions `v` and `to` as in [`Self::set`], or returns `None` if either is out of bounds.
+    /// Always bounds checks, even with the `unchecked` feature.
+    pub fn try_union(&mut self, v: usize, to: usize) -> Option<bool> {
+        let root_to = self.try_find(to)?;
+        let root_v = self.try_find(v)?;
+        if root_v == root_to {
+            return Some(false);
+        }
+        self.ptrs[root_v].set(T::from_usize(root_to));
+        let size = self.sizes[root_to].to_usize() + self.sizes[root_v].to_usize();
+        self.sizes[root_to] = T::from_usize(size);
+        self.len = T::from_usize(self.len.to_usize() - 1);
+        Some(true)
+    }
+    pub fn set(&mut self, v: usize, to: usize) -> bool {
+        self.set_with(v, to, |_, _| {})
     }
-    pub fn set(&mut self, v: usize, to: usize) {
-        debug_assert!(v <= self.ptrs.len());
-        debug_assert!(to <= self.ptrs.len());
+    /// Same as [`Self::set`], but calls `on_merge(surviving_root, absorbed_root)` if two
+    /// distinct components were merged.
+    pub fn set_with<F: FnMut(usize, usize)>(
+        &mut self,
+        v: usize,
+        to: usize,
+        mut on_merge: F,
+    ) -> bool {
+        if self.growable {
+            self.ensure_capacity(v.max(to));
+        }
+        assert!(v < self.ptrs.len(), "{v} is out of bounds");
+        assert!(to < self.ptrs.len(), "{to} is out of bounds");
         let root_to = self.get_compress(to);
         let root_v = self.get_compress(v);
+        let merged = self.union_roots(root_v, root_to);
+        if merged {
+            on_merge(root_to, root_v);
+        }
+        merged
+    }
+    /// Unions the components of `absorbed` and `keep`, guaranteeing that `keep`'s root remains
+    /// the root. This is what [`Self::set`] always does, but wrappers which pick the direction
+    /// of a union, such as [`SizedUnionFind`], may not.
+    #[inline]
+    pub fn union_into(&mut self, absorbed: usize, keep: usize) -> bool {
+        self.set(absorbed, keep)
+    }
+    /// Same as [`Self::set`], but skips finding the roots since both arguments must already
+    /// be roots, which is only checked in debug builds.
+    pub fn union_roots(&mut self, root_v: usize, root_to: usize) -> bool {
+        debug_assert!(self.is_root(root_v), "{root_v} is not a root");
+        debug_assert!(self.is_root(root_to), "{root_to} is not a root");
         if root_v == root_to {
-            return;
+            return false;
         }
-        idx!(self.ptrs, root_v).set(root_to as u32);
-        self.len -= 1;
+        idx!(self.ptrs, root_v).set(T::from_usize(root_to));
+        let size = self.sizes[root_to].to_usize() + self.sizes[root_v].to_usize();
+        self.sizes[root_to] = T::from_usize(size);
+        self.len = T::from_usize(self.len.to_usize() - 1);
+        true
+    }
+    /// Checks whether `a` and `b` are in the same component without compressing, unlike
+    /// [`UnionFindOp::same_set`].
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.get(a) == self.get(b)
     }
     /// Checks if a vertex is itself the root of a tree
     pub fn is_root(&self, v: usize) -> bool {
         self.ptrs
             .get(v)
-            .map(|p| p.get() as usize == v)
+            .map(|p| p.get().to_usize() == v)
             .unwrap_or(false)
     }
+    /// Number of elements in the component containing `v`.
+    pub fn size_of(&self, v: usize) -> usize {
+        self.sizes[self.get(v)].to_usize()
+    }
+    /// Counts the components by scanning for roots, rather than using the cached count.
+    pub fn num_components_recount(&self) -> usize {
+        (0..self.ptrs.len()).filter(|&i| self.is_root(i)).count()
+    }
+    /// In debug builds, panics if the cached number of components is wrong.
+    pub fn validate(&self) {
+        debug_assert_eq!(
+            self.num_components_recount(),
+            self.len.to_usize(),
+            "stale component count"
+        );
+    }
+    /// Moves `v` out of its component into a new singleton, if no other element points at it.
+    /// Returns false without modifying anything if `v` is a root or has dependents, since they
+    /// would be split off along with it. Finding dependents requires scanning every element.
+    pub fn make_singleton(&mut self, v: usize) -> bool {
+        if self.is_root(v) {
+            return false;
+        }
+        let has_dependents = self
+            .ptrs
+            .iter()
+            .enumerate()
+            .any(|(i, p)| i != v && p.get().to_usize() == v);
+        if has_dependents {
+            return false;
+        }
+        let root = self.get(v);
+        self.sizes[root] = T::from_usize(self.sizes[root].to_usize() - 1);
+        self.sizes[v] = T::from_usize(1);
+        idx!(self.ptrs, v).set(T::from_usize(v));
+        self.len = T::from_usize(self.len.to_usize() + 1);
+        true
+    }
+    /// The root and size of the largest component, preferring the smallest root on ties, or
+    /// `None` if there are no elements.
+    pub fn largest_component(&self) -> Option<(usize, usize)> {
+        self.component_sizes()
+            .reduce(|best, c| if c.1 > best.1 { c } else { best })
+    }
+    /// Number of components with a single element.
+    pub fn count_singletons(&self) -> usize {
+        self.component_sizes().filter(|&(_, s)| s == 1).count()
+    }
+    /// Number of components with at least two elements.
+    pub fn count_nontrivial(&self) -> usize {
+        self.component_sizes().filter(|&(_, s)| s > 1).count()
+    }
+    /// Roots of every component with fewer than `min_size` elements, in ascending order.
+    pub fn small_component_roots(&self, min_size: usize) -> Vec<usize> {
+        self.component_sizes()
+            .filter(|&(_, s)| s < min_size)
+            .map(|(r, _)| r)
+            .collect()
+    }
+    /// Yields the root and size of every component, in order of root.
+    pub fn component_sizes(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.ptrs.len())
+            .filter(|&i| self.is_root(i))
+            .map(|r| (r, self.sizes[r].to_usize()))
+    }
     pub fn compress(&mut self) {
         for i in 0..self.ptrs.len() {
             // compress it to last item always to flatten pointer chains.
@@ -175,48 +595,344 @@ impl UnionFind<u32> {
             }
         }
     }
-    pub fn extend_by(&mut self, n: usize) {
+    /// Appends `n` new singletons, returning their indices. Panics if the index type cannot
+    /// address that many elements, and aborts if they cannot be allocated, see
+    /// [`Self::try_extend_by`].
+    pub fn extend_by(&mut self, n: usize) -> Range<usize> {
+        match self.try_extend_by(n) {
+            Ok(r) => r,
+            Err(e) => panic!(
+                "UnionFind<{}> will overflow: {e}",
+                core::any::type_name::<T>()
+            ),
+        }
+    }
+    /// Appends `n` new singletons, returning their indices, or an error without modifying
+    /// `self` if the index type cannot address that many elements or they cannot be allocated.
+    pub fn try_extend_by(&mut self, n: usize) -> Result<Range<usize>, CapacityError> {
         let l = self.ptrs.len();
-        assert!(
-            (l + n) < u32::MAX as usize,
-            "UnionFind<u32> Will overflow with {}",
-            l + n
-        );
+        match l.checked_add(n) {
+            Some(total) if total < T::MAX => {}
+            _ => {
+                return Err(CapacityError::Overflow {
+                    requested: l.saturating_add(n),
+                    max: T::MAX - 1,
+                });
+            }
+        }
+        self.try_reserve(n)?;
+        for i in 0..n {
+            let s = checked!(l, +, i, "extend_by");
+            self.ptrs.push(Cell::new(T::from_usize(s)));
+        }
+        self.sizes.resize(l + n, T::from_usize(1));
+        self.len = T::from_usize(self.len.to_usize() + n);
+        Ok(l..l + n)
+    }
+    /// Reserves storage for at least `additional` more elements without adding any.
+    pub fn reserve(&mut self, additional: usize) {
+        self.ptrs.reserve(additional);
+        self.sizes.reserve(additional);
+    }
+    /// Same as [`Self::reserve`], but returns an error instead of aborting if the storage
+    /// cannot be allocated. The elements are unchanged either way.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.ptrs.try_reserve(additional)?;
+        self.sizes.try_reserve(additional)
+    }
+    /// Releases any excess capacity of the backing storage.
+    pub fn shrink_to_fit(&mut self) {
+        self.ptrs.shrink_to_fit();
+        self.sizes.shrink_to_fit();
+    }
+    /// Overwrites `dst` with a copy of `self`, reusing its buffers, so that nothing is
+    /// allocated if `dst` already has enough capacity.
+    pub fn clone_into(&self, dst: &mut Self) {
+        dst.ptrs.clear();
+        dst.ptrs
+            .extend(self.ptrs.iter().map(|p| Cell::new(p.get())));
+        dst.sizes.clear();
+        dst.sizes.extend_from_slice(&self.sizes);
+        dst.len = self.len;
+        dst.halve = self.halve;
+        dst.growable = self.growable;
+    }
+    /// Approximate number of bytes allocated on the heap, including excess capacity.
+    pub fn heap_bytes(&self) -> usize {
+        self.ptrs.capacity() * size_of::<Cell<T>>() + self.sizes.capacity() * size_of::<T>()
+    }
+    /// Grows with new singletons, if needed, so that `index` is a valid element.
+    pub fn ensure_capacity(&mut self, index: usize) {
+        if index >= self.ptrs.len() {
+            self.extend_by(index + 1 - self.ptrs.len());
+        }
+    }
+    /// Appends one new component per entry of `group_sizes`, each a contiguous block of that
+    /// size, and returns the range of indices of each.
+    pub fn extend_groups(&mut self, group_sizes: &[usize]) -> Vec<Range<usize>> {
+        group_sizes
+            .iter()
+            .map(|&size| {
+                let r = self.extend_by(size);
+                for i in r.start + 1..r.end {
+                    self.set(i, r.start);
+                }
+                r
+            })
+            .collect()
+    }
+    /// Number of elements which do not point directly at their root, i.e. would be shortened by
+    /// compression.
+    pub fn uncompressed_node_count(&self) -> usize {
+        (0..self.ptrs.len())
+            .filter(|&i| {
+                let p = self.ptrs[i].get().to_usize();
+                p != self.get(p)
+            })
+            .count()
+    }
+    /// Number of links from `v` to its root, without compressing.
+    pub fn depth(&self, mut v: usize) -> usize {
+        let mut d = 0;
+        while let p = idx!(self.ptrs, v).get().to_usize()
+            && p != v
+        {
+            v = p;
+            d += 1;
+        }
+        d
+    }
+    /// Largest [`Self::depth`] of any element, or 0 if there are no elements.
+    pub fn max_depth(&self) -> usize {
+        self.depth_stats().max
+    }
+    /// Mean number of links from each element in `r` to its root, without compressing, or 0
+    /// for an empty range.
+    pub fn avg_depth(&self, r: Range<usize>) -> f64 {
+        let n = r.len();
+        let total: usize = r.map(|v| self.depth(v)).sum();
+        if n == 0 { 0. } else { total as f64 / n as f64 }
+    }
+    /// Replaces every component with a single element, releasing the rest of the storage, and
+    /// returns the new index of each old element as in [`Self::component_labels`].
+    pub fn compact(&mut self) -> Vec<usize> {
+        let labels = self.component_labels();
+        self.ptrs.truncate(self.len.to_usize());
+        self.sizes.truncate(self.len.to_usize());
+        self.reset();
+        self.shrink_to_fit();
+        labels
+    }
+    /// Makes every element a singleton again, reusing the existing allocations.
+    pub fn reset(&mut self) {
+        for (i, p) in self.ptrs.iter().enumerate() {
+            p.set(T::from_usize(i));
+        }
+        self.sizes.fill(T::from_usize(1));
+        self.len = T::from_usize(self.ptrs.len());
+    }
+    /// Computes the min, max and mean depth along with the number of uncompressed elements, in a
+    /// single pass which never compresses. All fields are 0 if there are no elements.
+    pub fn depth_stats(&self) -> DepthStats {
+        let n = self.ptrs.len();
+        let mut depths = vec![usize::MAX; n];
+        let mut path = vec![];
         for i in 0..n {
-            let s = (l + i) as u32;
-            self.ptrs.push(Cell::new(s));
+            let mut v = i;
+            while depths[v] == usize::MAX {
+                let p = self.ptrs[v].get().to_usize();
+                if p == v {
+                    depths[v] = 0;
+                    break;
+                }
+                path.push(v);
+                v = p;
+            }
+            let mut d = depths[v];
+            for &u in path.iter().rev() {
+                d += 1;
+                depths[u] = d;
+            }
+            path.clear();
         }
-        self.len += n;
+        DepthStats {
+            min: depths.iter().copied().min().unwrap_or(0),
+            max: depths.iter().copied().max().unwrap_or(0),
+            mean: if n == 0 {
+                0.
+            } else {
+                depths.iter().sum::<usize>() as f64 / n as f64
+            },
+            uncompressed: depths.iter().filter(|&&d| d > 1).count(),
+        }
+    }
+    /// Panics if any element does not point directly at its root.
+    pub fn assert_compressed(&self) {
+        for (i, p) in self.ptrs.iter().enumerate() {
+            let p = p.get().to_usize();
+            assert!(self.is_root(p), "{i} points at {p}, which is not a root");
+        }
+    }
+    /// Finds the root of `v` with a single read. This is only correct if every element points
+    /// directly at its root, e.g. after [`Self::compress`].
+    #[inline]
+    pub fn find_flat(&self, v: usize) -> usize {
+        let p = idx!(self.ptrs, v).get().to_usize();
+        debug_assert!(self.is_root(p), "find_flat on non-flat element {v}");
+        p
+    }
+    /// Finds the root of every element in `r`, pointing each directly at its root.
+    pub fn find_range(&self, r: Range<usize>) -> Vec<usize> {
+        r.map(|i| self.get_compress(i)).collect()
+    }
+    /// The immediate parent of `v`, which is `v` itself for a root. Unlike [`Self::get`], this
+    /// follows only a single link.
+    #[inline]
+    pub fn parent(&self, v: usize) -> usize {
+        idx!(self.ptrs, v).get().to_usize()
+    }
+    /// Points `v` directly at `parent`, without updating the number of components or sizes.
+    /// The caller is responsible for keeping the forest acyclic and for calling
+    /// [`Self::repair_len`] if the number of roots changes. Sizes are not repaired.
+    pub fn set_parent_raw(&mut self, v: usize, parent: usize) {
+        assert!(parent < self.ptrs.len(), "{parent} is out of bounds");
+        idx!(self.ptrs, v).set(T::from_usize(parent));
+    }
+    /// Returns the `(parent, grandparent, root)` of `v`, where the grandparent is `None` if the
+    /// parent is a root.
+    pub fn ancestry(&self, v: usize) -> (usize, Option<usize>, usize) {
+        let p = idx!(self.ptrs, v).get().to_usize();
+        let gp = idx!(self.ptrs, p).get().to_usize();
+        (p, (gp != p).then_some(gp), self.get(gp))
+    }
+    /// Finds the root of `v` using Floyd's cycle detection, returning `None` if the parent
+    /// pointers contain a cycle instead of hanging.
+    pub fn find_safe(&self, v: usize) -> Option<usize> {
+        let parent = |v: usize| idx!(self.ptrs, v).get().to_usize();
+        let mut slow = v;
+        let mut fast = v;
+        loop {
+            for _ in 0..2 {
+                let n = parent(fast);
+                if n == fast {
+                    return Some(fast);
+                }
+                fast = n;
+            }
+            slow = parent(slow);
+            if slow == fast {
+                return None;
+            }
+        }
+    }
+    /// Finds the root of `v` without compressing, returning `None` if it is not reached within
+    /// `capacity()` hops or a pointer is out of bounds, either of which indicates corruption.
+    pub fn try_get(&self, mut v: usize) -> Option<usize> {
+        for _ in 0..=self.ptrs.len() {
+            let n = self.ptrs.get(v)?.get().to_usize();
+            if n == v {
+                return Some(v);
+            }
+            v = n;
+        }
+        None
     }
     /// Extract a subset of this union-find, assuming that it only maps within this range to
     /// itself.
     pub fn subset_clone(&self, r: Range<usize>) -> Self {
         let offset = r.start;
-        let len = r.end - offset;
-        let ptrs = vec![Cell::new(0); len];
+        let mut ptrs = Vec::with_capacity(r.len());
+        let mut sizes = vec![0; r.len()];
         let mut len = 0;
-        for (new_i, old_i) in r.clone().enumerate() {
-            let prev_v = self.ptrs[old_i].get();
-            len += (prev_v as usize == old_i) as usize;
-            assert!(r.contains(&(prev_v as usize)));
-            ptrs[new_i].set(prev_v - offset as u32);
+        for old_i in r.clone() {
+            let prev_v = self.ptrs[old_i].get().to_usize();
+            len += (prev_v == old_i) as usize;
+            assert!(r.contains(&prev_v));
+            let new_v = checked!(prev_v, -, offset, "subset_clone");
+            ptrs.push(Cell::new(T::from_usize(new_v)));
+            sizes[self.get(old_i) - offset] += 1;
+        }
+        Self {
+            ptrs,
+            sizes: sizes.into_iter().map(T::from_usize).collect(),
+            len: T::from_usize(len),
+            halve: self.halve,
+            growable: self.growable,
+        }
+    }
+    /// Splits into the elements before and after `mid`, shifting the pointers of the latter so
+    /// they start at 0. Panics if any component contains elements on both sides. This is the
+    /// inverse of [`Self::append`].
+    pub fn split_at(mut self, mid: usize) -> (Self, Self) {
+        assert!(mid <= self.ptrs.len(), "{mid} is out of bounds");
+        let right_ptrs = self.ptrs.split_off(mid);
+        let right_sizes = self.sizes.split_off(mid);
+        let mut left_len = 0;
+        for (i, p) in self.ptrs.iter().enumerate() {
+            let p = p.get().to_usize();
+            assert!(p < mid, "parent {p} of {i} straddles {mid}");
+            left_len += (p == i) as usize;
+        }
+        for (i, p) in right_ptrs.iter().enumerate() {
+            let parent = p.get().to_usize();
+            assert!(
+                parent >= mid,
+                "parent {parent} of {} straddles {mid}",
+                i + mid
+            );
+            p.set(T::from_usize(parent - mid));
+        }
+        let right = Self {
+            ptrs: right_ptrs,
+            sizes: right_sizes,
+            len: T::from_usize(self.len.to_usize() - left_len),
+            halve: self.halve,
+            growable: self.growable,
+        };
+        self.len = T::from_usize(left_len);
+        (self, right)
+    }
+    /// Places the forest of `other` after the elements of `self`, shifting its pointers so its
+    /// components are unchanged. This is the inverse of [`Self::subset_clone`].
+    pub fn append(&mut self, other: &Self) {
+        let offset = self.ptrs.len();
+        let n = offset + other.ptrs.len();
+        assert!(
+            n < T::MAX,
+            "UnionFind<{}> will overflow with {n}",
+            core::any::type_name::<T>(),
+        );
+        for p in &other.ptrs {
+            let s = checked!(p.get().to_usize(), +, offset, "append");
+            self.ptrs.push(Cell::new(T::from_usize(s)));
         }
-        Self { ptrs, len }
+        self.sizes.extend_from_slice(&other.sizes);
+        self.len = T::from_usize(self.len.to_usize() + other.len.to_usize());
     }
 
-    pub fn subset<'a>(&'a mut self, r: Range<usize>) -> BorrowedUnionFind<'a, u32> {
+    /// Borrows the elements in `r` as a union-find of their own, which must not point outside
+    /// of `r`. This is checked in debug builds.
+    pub fn subset<'a>(&'a mut self, r: Range<usize>) -> BorrowedUnionFind<'a, T> {
+        if cfg!(debug_assertions) {
+            for i in r.clone() {
+                let p = self.ptrs[i].get().to_usize();
+                assert!(r.contains(&p), "parent {p} of {i} escapes subset {r:?}");
+            }
+        }
         let own_len = self
             .ptrs
             .iter()
             .enumerate()
             .take(r.end)
             .skip(r.start)
-            .filter(|(i, v)| v.get() as usize == *i)
+            .filter(|(i, v)| v.get().to_usize() == *i)
             .count();
         let ptrs = &mut self.ptrs[r.clone()];
+        let sizes = &mut self.sizes[r.clone()];
         let len = &mut self.len;
         BorrowedUnionFind {
             ptrs,
+            sizes,
             len,
             own_len,
             r,
@@ -224,12 +940,72 @@ impl UnionFind<u32> {
     }
 }
 
-impl BorrowedUnionFind<'_, u32> {
+#[cfg(any(feature = "alloc", test))]
+impl UnionFind<usize> {
+    #[inline]
+    pub fn new(size: usize) -> Self {
+        Self::singletons(size)
+    }
+    /// Creates an empty union-find which grows as unions mention new elements, see
+    /// [`Self::with_growth`].
+    pub fn new_growable() -> Self {
+        Self::new(0).with_growth(true)
+    }
+}
+
+#[cfg(any(feature = "alloc", test))]
+impl UnionFind<u8> {
+    #[inline]
+    pub fn new_u8(len: usize) -> Self {
+        Self::singletons(len)
+    }
+}
+
+#[cfg(any(feature = "alloc", test))]
+impl UnionFind<u16> {
+    #[inline]
+    pub fn new_u16(len: usize) -> Self {
+        Self::singletons(len)
+    }
+}
+
+#[cfg(any(feature = "alloc", test))]
+impl UnionFind<u64> {
+    #[inline]
+    pub fn new_u64(len: usize) -> Self {
+        Self::singletons(len)
+    }
+}
+
+#[cfg(any(feature = "alloc", test))]
+impl UnionFind<u32> {
+    #[deprecated(note = "use `UnionFind::<u32>::with_capacity` instead")]
+    #[inline]
+    pub fn new_u32(len: usize) -> Self {
+        Self::singletons(len)
+    }
+
+    /// The parent of each element as a contiguous slice, e.g. for uploading to a GPU without a
+    /// copy. This takes `&mut self` so that no `&self` method may compress paths through the
+    /// cells while the slice is alive.
+    pub fn parents_slice(&mut self) -> &[u32] {
+        // Safety: `Cell<u32>` has the same in-memory representation as `u32`, and the exclusive
+        // borrow prevents any writes through the cells for the lifetime of the returned slice.
+        unsafe { &*(self.ptrs.as_slice() as *const [Cell<u32>] as *const [u32]) }
+    }
+}
+
+#[cfg(any(feature = "alloc", test))]
+impl<T: UfIndex> BorrowedUnionFind<'_, T> {
     #[inline]
     pub fn get(&self, mut v: usize) -> usize {
         debug_assert!(self.r.contains(&(v + self.r.start)), "{v:?} {:?}", self.r);
-        while let n = idx!(self.ptrs, v).get() as usize - self.r.start
-            && n != v
+        while let n = checked!(
+            idx!(self.ptrs, v).get().to_usize(),
+            -,
+            self.r.start,
+            "BorrowedUnionFind::get"
+        ) && n != v
         {
             v = n;
         }
@@ -237,96 +1013,179 @@ impl BorrowedUnionFind<'_, u32> {
     }
     pub fn get_compress(&self, v: usize) -> usize {
         let dst = self.get(v);
-        unsafe { self.ptrs.get_unchecked(v) }.set((dst + self.r.start) as u32);
+        let global = checked!(dst, +, self.r.start, "BorrowedUnionFind::get_compress");
+        idx!(self.ptrs, v).set(T::from_usize(global));
         dst
     }
-    pub fn set(&mut self, v: usize, to: usize) {
+    pub fn set(&mut self, v: usize, to: usize) -> bool {
         debug_assert!(self.r.contains(&(v + self.r.start)));
         debug_assert!(self.r.contains(&(to + self.r.start)));
 
         let root_to = self.get_compress(to);
         let root_v = self.get_compress(v);
         if root_v == root_to {
-            return;
+            return false;
         }
-        idx!(self.ptrs, root_v).set((root_to + self.r.start) as u32);
-        *self.len -= 1;
+        let global = checked!(root_to, +, self.r.start, "BorrowedUnionFind::set");
+        idx!(self.ptrs, root_v).set(T::from_usize(global));
+        let size = self.sizes[root_to].to_usize() + self.sizes[root_v].to_usize();
+        self.sizes[root_to] = T::from_usize(size);
+        *self.len = T::from_usize(self.len.to_usize() - 1);
         self.own_len -= 1;
+        true
+    }
+    /// Checks whether `a` and `b` are in the same component without compressing, unlike
+    /// [`UnionFindOp::same_set`].
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.get(a) == self.get(b)
     }
     /// Checks if a vertex is itself the root of a tree
     pub fn is_root(&self, v: usize) -> bool {
         self.ptrs
             .get(v)
-            .map(|p| p.get() as usize == v)
+            .map(|p| p.get().to_usize() == v + self.r.start)
             .unwrap_or(false)
     }
-}
-
-macro_rules! impl_basic {
-    ($t: ty) => {
-        impl UnionFindOp for $t {
-            #[inline]
-            fn find(&self, v: usize) -> usize {
-                self.get_compress(v)
-            }
-            #[inline]
-            fn union(&mut self, v: usize, to: usize) {
-                self.set(v, to)
-            }
-            #[inline]
-            fn len(&self) -> usize {
-                self.len
-            }
-            #[inline]
-            fn capacity(&self) -> usize {
-                self.ptrs.len()
+    /// Counts the components by scanning for roots, rather than using the cached count.
+    pub fn num_components_recount(&self) -> usize {
+        (0..self.ptrs.len()).filter(|&i| self.is_root(i)).count()
+    }
+    /// In debug builds, panics if the cached number of components is wrong.
+    pub fn validate(&self) {
+        debug_assert_eq!(
+            self.num_components_recount(),
+            self.own_len,
+            "stale component count"
+        );
+    }
+    /// Points every element of this subset directly at its root. Does not change the number
+    /// of components.
+    pub fn compress(&mut self) {
+        for i in 0..self.ptrs.len() {
+            if self.get(i) != i {
+                self.get_compress(i);
             }
         }
-    };
-    (BORROWED $t: ty) => {
-        impl UnionFindOp for $t {
-            #[inline]
-            fn find(&self, v: usize) -> usize {
-                self.get_compress(v)
-            }
-            #[inline]
-            fn union(&mut self, v: usize, to: usize) {
-                self.set(v, to)
-            }
-            #[inline]
-            fn len(&self) -> usize {
-                self.own_len
-            }
-            #[inline]
-            fn capacity(&self) -> usize {
-                self.ptrs.len()
-            }
+    }
+    /// Copies this subset into an owned union-find in local coordinates. Panics if any element
+    /// points outside of the borrowed range.
+    pub fn to_owned(&self) -> UnionFind<T> {
+        let ptrs = self
+            .ptrs
+            .iter()
+            .map(|p| {
+                let p = p.get().to_usize();
+                assert!(self.r.contains(&p), "{p} is outside of {:?}", self.r);
+                Cell::new(T::from_usize(p - self.r.start))
+            })
+            .collect::<Vec<_>>();
+        let len = ptrs
+            .iter()
+            .enumerate()
+            .filter(|(i, p)| p.get().to_usize() == *i)
+            .count();
+        UnionFind {
+            ptrs,
+            sizes: self.sizes.to_vec(),
+            len: T::from_usize(len),
+            halve: false,
+            growable: false,
         }
-    };
+    }
 }
 
-impl_basic!(UnionFind<usize>);
-impl_basic!(UnionFind<u32>);
-impl_basic!(BORROWED BorrowedUnionFind<'_, u32>);
-
-#[test]
-fn test_subset_clone() {
-    let mut v = UnionFind::new_u32(32);
-    let s = v.subset_clone(16..32);
-    assert_eq!(s.curr_len(), 16);
-    assert_eq!(s.capacity(), 16);
-
-    v.set(18, 19);
-    let s = v.subset_clone(16..32);
-    assert_eq!(s.curr_len(), 15);
-    assert_eq!(s.capacity(), 16);
-    assert!(!s.is_root(2));
-    assert_eq!(s.get(2), 3);
+/// Checks that no element of `a` shares a component with any element of `b`, so that subsets
+/// over both ranges can be processed independently.
+#[cfg(any(feature = "alloc", test))]
+pub fn ranges_independent(uf: &UnionFind<u32>, a: Range<usize>, b: Range<usize>) -> bool {
+    let mut is_root = vec![false; uf.capacity()];
+    for i in a {
+        is_root[uf.get(i)] = true;
+    }
+    b.map(|i| uf.get(i)).all(|r| !is_root[r])
+}
+
+#[cfg(any(feature = "alloc", test))]
+impl<T: UfIndex> UnionFindOp for UnionFind<T> {
+    #[inline]
+    fn find(&self, v: usize) -> usize {
+        if self.halve {
+            self.get_halve(v)
+        } else {
+            self.get_compress(v)
+        }
+    }
+    #[inline]
+    fn find_no_compress(&self, v: usize) -> usize {
+        self.get(v)
+    }
+    #[inline]
+    fn is_root(&self, v: usize) -> bool {
+        self.is_root(v)
+    }
+    #[inline]
+    fn union(&mut self, v: usize, to: usize) -> bool {
+        self.set(v, to)
+    }
+    #[inline]
+    fn len(&self) -> usize {
+        self.len.to_usize()
+    }
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.ptrs.len()
+    }
+}
+
+#[cfg(any(feature = "alloc", test))]
+impl<T: UfIndex> UnionFindOp for BorrowedUnionFind<'_, T> {
+    #[inline]
+    fn find(&self, v: usize) -> usize {
+        self.get_compress(v)
+    }
+    #[inline]
+    fn find_no_compress(&self, v: usize) -> usize {
+        self.get(v)
+    }
+    #[inline]
+    fn is_root(&self, v: usize) -> bool {
+        self.is_root(v)
+    }
+    #[inline]
+    fn union(&mut self, v: usize, to: usize) -> bool {
+        self.set(v, to)
+    }
+    #[inline]
+    fn len(&self) -> usize {
+        self.own_len
+    }
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.ptrs.len()
+    }
+}
+
+#[test]
+fn test_subset_clone() {
+    let mut v = UnionFind::<u32>::with_capacity(32);
+    let s = v.subset_clone(16..32);
+    assert_eq!(s.curr_len(), 16);
+    assert_eq!(s.capacity(), 16);
+    s.validate();
+
+    v.set(18, 19);
+    v.validate();
+    let s = v.subset_clone(16..32);
+    s.validate();
+    assert_eq!(s.curr_len(), 15);
+    assert_eq!(s.capacity(), 16);
+    assert!(!s.is_root(2));
+    assert_eq!(s.get(2), 3);
 }
 
 #[test]
 fn test_subset() {
-    let mut v = UnionFind::new_u32(32);
+    let mut v = UnionFind::<u32>::with_capacity(32);
     let mut s = v.subset(16..32);
     assert_eq!(s.curr_len(), 16);
     assert_eq!(s.capacity(), 16);
@@ -335,15 +1194,1224 @@ fn test_subset() {
     assert_eq!(s.get(1), 1);
     assert_eq!(s.get_compress(1), 1);
     s.set(1, 2);
+    s.validate();
     assert_eq!(s.curr_len(), 15);
     assert_eq!(s.get(1), 2);
 
     assert_eq!(v.curr_len(), 31);
+    v.validate();
 
     v.set(20, 21);
     let s = v.subset(16..32);
+    s.validate();
     assert_eq!(s.curr_len(), 14);
     assert_eq!(s.capacity(), 16);
     assert!(!s.is_root(4));
+    assert!(s.is_root(5));
     assert_eq!(s.get(4), 5);
 }
+
+#[test]
+fn test_subset_clone_usize() {
+    let mut v = UnionFind::new(32);
+    let s = v.subset_clone(16..32);
+    assert_eq!(s.curr_len(), 16);
+    assert_eq!(s.capacity(), 16);
+    s.validate();
+
+    v.set(18, 19);
+    v.set(20, 19);
+    let s = v.subset_clone(16..32);
+    s.validate();
+    assert_eq!(s.curr_len(), 14);
+    assert_eq!(s.capacity(), 16);
+    assert!(!s.is_root(2));
+    assert_eq!(s.get(4), 3);
+    assert_eq!(s.size_of(2), 3);
+}
+
+#[test]
+#[should_panic]
+fn test_subset_clone_out_of_range() {
+    let mut v = UnionFind::new(8);
+    v.set(5, 2);
+    v.subset_clone(4..8);
+}
+
+#[test]
+fn test_subset_usize() {
+    let mut v = UnionFind::new(32);
+    let mut s = v.subset(16..32);
+    assert_eq!(s.curr_len(), 16);
+    assert_eq!(s.capacity(), 16);
+
+    assert_eq!(s.get(1), 1);
+    assert_eq!(s.get_compress(1), 1);
+    s.set(1, 2);
+    assert_eq!(s.curr_len(), 15);
+    assert_eq!(s.get(1), 2);
+    assert!(s.same_set(1, 2));
+
+    assert_eq!(v.curr_len(), 31);
+    assert_eq!(v.get(17), 18);
+    assert_eq!(v.size_of(17), 2);
+
+    v.set(20, 21);
+    let s = v.subset(16..32);
+    assert_eq!(s.curr_len(), 14);
+    assert_eq!(s.capacity(), 16);
+    assert!(!s.is_root(4));
+    assert!(s.is_root(5));
+    assert_eq!(s.get(4), 5);
+}
+
+#[test]
+fn test_union_chain() {
+    let mut v = UnionFind::new(8);
+    assert_eq!(v.union_chain(&[3]), None);
+    let root = v.union_chain(&[1, 5, 2, 7]).unwrap();
+    for i in [1, 5, 2, 7] {
+        assert_eq!(v.get(i), root);
+    }
+    assert_eq!(v.curr_len(), 5);
+}
+
+#[test]
+fn test_repair_len() {
+    let mut v = UnionFind::<u32>::with_capacity(8);
+    v.set(0, 1);
+    v.set(2, 3);
+    assert!(!v.repair_len());
+    v.len = 2;
+    assert!(v.repair_len());
+    assert_eq!(v.curr_len(), 6);
+    assert!(!v.repair_len());
+}
+
+#[test]
+fn test_same_set_many() {
+    let mut v = UnionFind::new(6);
+    v.set(0, 1);
+    v.set(1, 4);
+    v.set(2, 3);
+    let pairs = [(0, 4), (0, 2), (3, 2), (5, 5), (4, 5)];
+    let mut out = [false; 5];
+    v.same_set_many(&pairs, &mut out);
+    for (&(a, b), o) in pairs.iter().zip(out) {
+        assert_eq!(v.same_set(a, b), o);
+    }
+    assert_eq!(out, [true, false, true, true, false]);
+}
+
+#[cfg(feature = "fuzzing")]
+#[test]
+#[should_panic(expected = "underflow in BorrowedUnionFind::get")]
+fn test_fuzzing_underflow() {
+    let mut v = UnionFind::<u32>::with_capacity(32);
+    let s = v.subset(16..32);
+    // points outside of the borrowed range, which `subset` rejects in debug builds.
+    s.ptrs[0].set(2);
+    s.get(0);
+}
+
+#[test]
+fn test_union_runs() {
+    let mut v = UnionFind::new(8);
+    v.union_runs(&[(0, 3), (4, 2)]);
+    assert_eq!(v.curr_len(), 5);
+    assert!(v.same_set(0, 1) && v.same_set(1, 2));
+    assert!(v.same_set(4, 5));
+    assert!(!v.same_set(2, 3));
+    assert!(v.is_root(3) && v.is_root(6) && v.is_root(7));
+}
+
+#[test]
+fn test_parents_slice() {
+    let mut v = UnionFind::<u32>::with_capacity(8);
+    v.set(0, 1);
+    v.set(1, 5);
+    v.set(6, 7);
+    let expected = v.ptrs.iter().map(Cell::get).collect::<Vec<_>>();
+    assert_eq!(v.parents_slice(), expected);
+}
+
+#[test]
+fn test_union_by_external() {
+    let mut v = UnionFind::new(6);
+    let id = |i: usize| (5 - i) as u64;
+    assert!(v.union_by_external(0, 1, id));
+    assert_eq!(v.get(0), 1);
+    assert!(v.union_by_external(4, 2, id));
+    assert_eq!(v.get(2), 4);
+    assert!(v.union_by_external(0, 2, id));
+    assert_eq!(v.get(1), 4);
+    assert!(!v.union_by_external(1, 4, id));
+    assert_eq!(v.get(5), 5);
+}
+
+#[test]
+fn test_uncompressed_node_count() {
+    let mut v = UnionFind::new(6);
+    let mut u = UnionFind::<u32>::with_capacity(6);
+    for (a, b) in [(0, 1), (1, 2), (2, 3), (4, 5)] {
+        v.set(a, b);
+        u.set(a, b);
+    }
+    // 0 -> 1 -> 2 -> 3, so only 0 and 1 do not point at their root.
+    assert_eq!(v.uncompressed_node_count(), 2);
+    assert_eq!(u.uncompressed_node_count(), 2);
+    v.compress();
+    u.compress();
+    assert_eq!(v.uncompressed_node_count(), 0);
+    assert_eq!(u.uncompressed_node_count(), 0);
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_random_unions() {
+    use rand::SeedableRng;
+    let run = || {
+        let mut v = UnionFind::new(100);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let merges = v.random_unions(50, &mut rng);
+        assert_eq!(v.curr_len(), 100 - merges);
+        merges
+    };
+    assert_eq!(run(), 49);
+    assert_eq!(run(), 49);
+}
+
+#[test]
+fn test_assert_compressed() {
+    let mut v = UnionFind::<u32>::with_capacity(6);
+    v.union_chain(&[0, 1, 2, 3]);
+    v.compress();
+    v.assert_compressed();
+}
+
+#[test]
+#[should_panic(expected = "which is not a root")]
+fn test_assert_compressed_after_union() {
+    let mut v = UnionFind::new(6);
+    v.union_chain(&[0, 1, 2, 3]);
+    v.compress();
+    v.assert_compressed();
+    v.set(3, 5);
+    v.assert_compressed();
+}
+
+#[test]
+fn test_borrowed_to_owned() {
+    let mut v = UnionFind::<u32>::with_capacity(32);
+    let mut s = v.subset(16..32);
+    s.set(1, 2);
+    s.set(2, 7);
+    s.set(10, 11);
+    let owned = s.to_owned();
+    assert_eq!(owned.capacity(), 16);
+    assert_eq!(owned.curr_len(), s.curr_len());
+    for i in 0..16 {
+        assert_eq!(owned.get(i), s.get(i));
+    }
+}
+
+#[test]
+fn test_find_safe() {
+    let mut v = UnionFind::<u32>::with_capacity(6);
+    v.union_chain(&[0, 1, 2, 3]);
+    assert_eq!(v.find_safe(0), Some(v.get(0)));
+    assert_eq!(v.find_safe(5), Some(5));
+    // 3 -> 4 -> 5 -> 3
+    v.ptrs[3].set(4);
+    v.ptrs[4].set(5);
+    v.ptrs[5].set(3);
+    assert_eq!(v.find_safe(0), None);
+    assert_eq!(v.find_safe(4), None);
+
+    let v = UnionFind::new(2);
+    v.ptrs[0].set(1);
+    v.ptrs[1].set(0);
+    assert_eq!(v.find_safe(0), None);
+}
+
+#[test]
+fn test_union_intervals() {
+    let mut v = UnionFind::new(8);
+    v.union_intervals(&[(0, 2), (1, 4), (6, 6)]);
+    assert_eq!(v.curr_len(), 4);
+    for i in 1..=4 {
+        assert!(v.same_set(0, i));
+    }
+    assert!(!v.same_set(4, 5));
+    assert!(v.is_root(6) && v.is_root(7));
+}
+
+#[test]
+fn test_ranges_independent() {
+    let mut v = UnionFind::<u32>::with_capacity(12);
+    v.union_chain(&[0, 1, 2]);
+    v.union(4, 5);
+    v.union(8, 9);
+    assert!(ranges_independent(&v, 0..4, 4..8));
+    assert!(ranges_independent(&v, 0..6, 6..12));
+    v.union(3, 10);
+    assert!(!ranges_independent(&v, 0..6, 6..12));
+    assert!(ranges_independent(&v, 0..3, 6..12));
+}
+
+#[test]
+fn test_find_flat() {
+    let mut v = UnionFind::new(8);
+    let mut u = UnionFind::<u32>::with_capacity(8);
+    for (a, b) in [(0, 1), (1, 2), (2, 3), (5, 6)] {
+        v.set(a, b);
+        u.set(a, b);
+    }
+    v.compress();
+    u.compress();
+    for i in 0..8 {
+        assert_eq!(v.find_flat(i), v.find(i));
+        assert_eq!(u.find_flat(i), u.find(i));
+    }
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "find_flat on non-flat element")]
+fn test_find_flat_not_flat() {
+    let mut v = UnionFind::<u32>::with_capacity(4);
+    v.union_chain(&[0, 1, 2]);
+    v.find_flat(0);
+}
+
+#[test]
+fn test_extend_groups() {
+    let mut v = UnionFind::<u32>::with_capacity(4);
+    v.set(0, 1);
+    let ranges = v.extend_groups(&[3, 0, 2]);
+    assert_eq!(ranges, vec![4..7, 7..7, 7..9]);
+    assert_eq!(v.capacity(), 9);
+    assert_eq!(v.curr_len(), 5);
+    assert!(v.same_set(4, 6) && v.same_set(7, 8));
+    assert!(!v.same_set(6, 7) && !v.same_set(3, 4));
+
+    let mut v = UnionFind::new(0);
+    assert_eq!(v.extend_groups(&[3, 2]), vec![0..3, 3..5]);
+    assert_eq!(v.curr_len(), 2);
+}
+
+#[test]
+fn test_find_no_alloc() {
+    let n = 1000;
+    let mut v = UnionFind::<u32>::with_capacity(n);
+    let mut u = UnionFind::new(n);
+    for i in 0..n - 1 {
+        v.set(i, i + 1);
+        u.set(i, i + 1);
+    }
+    assert_eq!(v.find_no_alloc(0), n - 1);
+    assert_eq!(u.find_no_alloc(0), n - 1);
+    // every other node on the path now skips a level.
+    assert_eq!(v.ptrs[0].get(), 2);
+    assert_eq!(u.ptrs[2].get(), 4);
+    for i in 0..n {
+        assert_eq!(v.find_no_alloc(i), n - 1);
+    }
+}
+
+#[test]
+fn test_connected() {
+    let mut v = UnionFind::new(6);
+    let mut u = UnionFind::<u32>::with_capacity(6);
+    let mut a = AtomicUnionFind::new(6);
+    for (x, y) in [(0, 1), (1, 2), (4, 5)] {
+        v.set(x, y);
+        u.set(x, y);
+        a.set(x, y);
+    }
+    for i in 0..6 {
+        assert!(v.connected(i, i) && v.same_set(i, i));
+        assert!(u.connected(i, i) && a.connected(i, i));
+        for j in 0..6 {
+            assert_eq!(v.connected(i, j), v.same_set(i, j));
+            assert_eq!(u.connected(i, j), v.connected(i, j));
+            assert_eq!(a.connected(i, j), v.connected(i, j));
+        }
+    }
+    let mut s = u.subset(3..6);
+    s.set(0, 1);
+    assert!(s.connected(0, 2) && s.same_set(0, 2));
+    assert!(s.connected(1, 1));
+}
+
+#[cfg(not(feature = "unchecked"))]
+#[test]
+#[should_panic]
+fn test_connected_out_of_range() {
+    UnionFind::<u32>::with_capacity(4).connected(0, 4);
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn test_atomic_connected_out_of_range() {
+    AtomicUnionFind::new(4).connected(4, 0);
+}
+
+#[test]
+fn test_ancestry() {
+    let mut v = UnionFind::<u32>::with_capacity(5);
+    v.set(0, 1);
+    v.set(1, 2);
+    v.set(2, 3);
+    assert_eq!(v.ancestry(0), (1, Some(2), 3));
+    assert_eq!(v.ancestry(2), (3, None, 3));
+    assert_eq!(v.ancestry(3), (3, None, 3));
+    let mut u = UnionFind::new(4);
+    u.union_chain(&[0, 1, 2]);
+    assert_eq!(u.ancestry(0), (1, Some(2), 2));
+}
+
+#[test]
+fn test_size_of() {
+    let n = 1000;
+    let mut v = UnionFind::new(n);
+    let mut u = UnionFind::<u32>::with_capacity(n);
+    for i in 0..n - 1 {
+        v.set(i, i + 1);
+        u.set(i + 1, i);
+    }
+    assert_eq!(v.size_of(v.get(0)), n);
+    assert_eq!(u.size_of(u.get(0)), n);
+    v.compress();
+    u.compress();
+    assert!((0..n).all(|i| v.size_of(i) == n && u.size_of(i) == n));
+
+    u.extend_by(2);
+    assert_eq!(u.size_of(n), 1);
+    u.set(n, n + 1);
+    assert_eq!(u.size_of(n), 2);
+    let mut s = u.subset(n..n + 2);
+    assert_eq!(s.to_owned().size_of(0), 2);
+    s.set(0, 1);
+    assert_eq!(u.size_of(n + 1), 2);
+}
+
+#[test]
+fn test_get_halve() {
+    let n = 1024;
+    let depths = |halve: bool| {
+        let v = UnionFind::<u32>::with_capacity(n).with_path_halving(halve);
+        // `set` compresses, so build a single long chain directly.
+        for i in 0..n - 1 {
+            v.ptrs[i].set(i as u32 + 1);
+        }
+        for i in (0..n).step_by(64) {
+            assert_eq!(v.find(i), n - 1);
+        }
+        (0..n)
+            .map(|mut i| {
+                let mut d = 0;
+                while v.ptrs[i].get() as usize != i {
+                    i = v.ptrs[i].get() as usize;
+                    d += 1;
+                }
+                d
+            })
+            .sum::<usize>()
+    };
+    let (compressed, halved) = (depths(false), depths(true));
+    assert!(halved * 2 < compressed, "{halved} {compressed}");
+
+    let u = UnionFind::new(4);
+    u.ptrs[0].set(1);
+    u.ptrs[1].set(2);
+    u.ptrs[2].set(3);
+    assert_eq!(u.get_halve(0), 3);
+    assert_eq!(u.ptrs[0].get(), 2);
+}
+
+#[test]
+fn test_roots() {
+    let mut v = UnionFind::new(10);
+    let mut u = UnionFind::<u32>::with_capacity(10);
+    for (a, b) in [(0, 3), (3, 7), (2, 9), (9, 8), (5, 5)] {
+        v.set(a, b);
+        u.set(a, b);
+    }
+    assert_eq!(v.roots().count(), v.curr_len());
+    assert_eq!(u.roots().count(), u.curr_len());
+    assert_eq!(v.roots().collect::<Vec<_>>(), vec![1, 4, 5, 6, 7, 8]);
+    assert_eq!(u.roots().take(2).collect::<Vec<_>>(), vec![1, 4]);
+
+    let mut roots = v.roots();
+    assert_eq!(roots.len(), v.curr_len());
+    roots.nth(4);
+    assert_eq!(roots.len(), 1);
+    assert_eq!(roots.next(), Some(8));
+    assert_eq!((roots.len(), roots.next(), roots.next()), (0, None, None));
+}
+
+#[test]
+fn test_set_bounds() {
+    let mut v = UnionFind::new(4);
+    v.set(3, 0);
+    assert!(v.same_set(0, 3));
+    let mut u = UnionFind::<u32>::with_capacity(4);
+    u.set(3, 0);
+    assert!(u.same_set(0, 3));
+}
+
+#[test]
+#[should_panic(expected = "4 is out of bounds")]
+fn test_set_out_of_bounds() {
+    UnionFind::new(4).set(4, 0);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "4 is out of bounds")]
+fn test_set_out_of_bounds_u32() {
+    UnionFind::<u32>::with_capacity(4).set(0, 4);
+}
+
+#[test]
+fn test_avg_depth() {
+    let mut v = UnionFind::<u32>::with_capacity(8);
+    // 0 -> 1 -> 2 -> 3, with depths 3, 2, 1, 0.
+    for i in 0..3 {
+        v.set(i, i + 1);
+    }
+    v.ptrs[0].set(1);
+    v.ptrs[1].set(2);
+    assert_eq!(v.avg_depth(0..4), 1.5);
+    assert_eq!(v.avg_depth(4..8), 0.);
+    assert_eq!(v.avg_depth(0..0), 0.);
+    assert_eq!(v.ptrs[0].get(), 1);
+    let u = UnionFind::new(3);
+    u.ptrs[0].set(1);
+    u.ptrs[1].set(2);
+    assert_eq!(u.avg_depth(0..3), 1.);
+}
+
+#[test]
+fn test_small_index() {
+    let mut v = UnionFind::new_u16(60000);
+    for i in 1..60000 {
+        v.set(i - 1, i);
+    }
+    assert_eq!(v.curr_len(), 1);
+    assert_eq!(v.size_of(0), 60000);
+
+    let mut v = UnionFind::new_u8(8);
+    v.union_chain(&[0, 3, 7]);
+    assert_eq!(v.curr_len(), 6);
+    assert!(v.connected(0, 7));
+    let v = UnionFind::new_u64(4);
+    assert!(v.roots().eq(0..4));
+}
+
+#[test]
+#[should_panic(expected = "UnionFind<u8> will overflow")]
+fn test_small_index_overflow() {
+    UnionFind::new_u8(255);
+}
+
+#[test]
+fn test_depth_stats() {
+    let mut v = UnionFind::<u32>::with_capacity(6);
+    // 0 -> 1 -> 2 -> 3, and 4 -> 5, with depths 3, 2, 1, 0, 1, 0.
+    for (a, b) in [(0, 1), (1, 2), (2, 3), (4, 5)] {
+        v.set(a, b);
+    }
+    v.ptrs[0].set(1);
+    v.ptrs[1].set(2);
+    let stats = v.depth_stats();
+    assert_eq!(stats.min, 0);
+    assert_eq!(stats.max, 3);
+    assert_eq!(stats.mean, 7. / 6.);
+    assert_eq!(stats.uncompressed, 2);
+    assert_eq!(stats.uncompressed, v.uncompressed_node_count());
+    assert_eq!(v.ptrs[0].get(), 1);
+
+    let stats = UnionFind::new(0).depth_stats();
+    assert_eq!(
+        (stats.min, stats.max, stats.mean, stats.uncompressed),
+        (0, 0, 0., 0)
+    );
+}
+
+#[test]
+fn test_reset() {
+    let mut v = UnionFind::new(6);
+    let mut u = UnionFind::<u32>::with_capacity(6);
+    for (a, b) in [(0, 1), (1, 2), (4, 5)] {
+        v.set(a, b);
+        u.set(a, b);
+    }
+    v.reset();
+    u.reset();
+    assert_eq!(v.curr_len(), v.capacity());
+    assert_eq!(u.curr_len(), u.capacity());
+    assert!((0..6).all(|i| v.is_root(i) && u.is_root(i)));
+    assert!((0..6).all(|i| v.size_of(i) == 1));
+}
+
+#[test]
+fn test_union_returns_merged() {
+    let mut v = UnionFind::new(4);
+    let mut u = UnionFind::<u32>::with_capacity(4);
+    assert!(v.union(0, 1) && u.union(0, 1));
+    assert!(!v.union(0, 1) && !u.union(1, 0));
+    assert!(v.set(2, 1) && u.set(2, 1));
+    assert!(!v.set(0, 2) && !u.set(0, 2));
+
+    let mut w = UnionFind::<u32>::with_capacity(4);
+    let mut b = w.subset(2..4);
+    assert!(b.union(0, 1));
+    assert!(!b.union(1, 0));
+}
+
+#[test]
+fn test_try_find_union() {
+    let mut v = UnionFind::new(4);
+    let mut u = UnionFind::<u32>::with_capacity(4);
+    assert_eq!(v.try_union(0, 1), Some(true));
+    assert_eq!(u.try_union(0, 1), Some(true));
+    assert_eq!(v.try_union(1, 0), Some(false));
+    assert_eq!(v.try_union(0, 4), None);
+    assert_eq!(u.try_union(usize::MAX, 2), None);
+    assert_eq!(v.curr_len(), 3);
+    assert_eq!(u.curr_len(), 3);
+    assert_eq!(v.try_find(0), Some(v.get(1)));
+    assert_eq!(u.try_find(3), Some(3));
+    assert_eq!(v.try_find(4), None);
+    assert_eq!(u.try_find(100), None);
+    assert_eq!(u.size_of(0), 2);
+}
+
+#[test]
+fn test_append() {
+    let mut v = UnionFind::<u32>::with_capacity(32);
+    for i in 0..16 {
+        v.set(2 * i, 2 * i + 1);
+    }
+    let mut lo = v.subset_clone(0..16);
+    let mut hi = v.subset_clone(16..32);
+    lo.set(0, 2);
+    hi.set(4, 7);
+    hi.set(9, 7);
+    lo.append(&hi);
+    v.set(0, 2);
+    v.set(20, 23);
+    v.set(25, 23);
+    assert_eq!(lo.capacity(), 32);
+    assert_eq!(lo.curr_len(), v.curr_len());
+    assert!(lo.same_partition(&v));
+    assert!((0..32).all(|i| lo.size_of(i) == v.size_of(i)));
+}
+
+#[test]
+fn test_borrowed_compress() {
+    let mut v = UnionFind::<u32>::with_capacity(16);
+    let mut b = v.subset(8..16);
+    for i in 0..7 {
+        b.set(i, i + 1);
+    }
+    // Build a deep chain 0 -> 1 -> ... -> 7 without compression.
+    for i in 0..7 {
+        b.ptrs[i].set(i as u32 + 9);
+    }
+    let len = (b.curr_len(), *b.len);
+    b.compress();
+    assert_eq!((b.curr_len(), *b.len), len);
+    assert!((0..8).all(|i| b.get(i) == 7 && b.is_root(b.get(i))));
+    assert!((0..8).all(|i| b.ptrs[i].get() == 15));
+    assert!((0..8).all(|i| v.get(i) == i));
+}
+
+#[test]
+fn test_is_empty() {
+    let empty = UnionFind::new(0);
+    assert!(empty.is_empty());
+    assert_eq!(empty.len(), 0);
+    let mut uf = UnionFind::<u32>::with_capacity(5);
+    assert!(!uf.is_empty());
+    uf.union_chain(&[0, 1, 2, 3, 4]);
+    assert!(!uf.is_empty());
+    assert_eq!(uf.len(), 1);
+    assert_eq!(uf.len(), uf.curr_len());
+    assert_eq!(uf.capacity(), 5);
+}
+
+#[test]
+fn test_ensure_capacity() {
+    let mut v = UnionFind::new(4);
+    let mut u = UnionFind::<u32>::with_capacity(4);
+    v.set(0, 1);
+    u.set(2, 3);
+    v.ensure_capacity(10);
+    u.ensure_capacity(10);
+    assert_eq!((v.capacity(), v.curr_len()), (11, 10));
+    assert_eq!((u.capacity(), u.curr_len()), (11, 10));
+    assert!((4..=10).all(|i| v.is_root(i) && u.is_root(i) && v.size_of(i) == 1));
+    v.ensure_capacity(3);
+    assert_eq!(v.capacity(), 11);
+}
+
+#[test]
+fn test_default_with_capacity() {
+    assert_eq!(UnionFind::<usize>::default().capacity(), 0);
+    assert_eq!(UnionFind::<u32>::default().capacity(), 0);
+    assert_eq!(UnionFind::<usize>::with_capacity(8).curr_len(), 8);
+    assert_eq!(UnionFind::<u32>::with_capacity(8).curr_len(), 8);
+    #[allow(deprecated)]
+    let uf = UnionFind::new_u32(8);
+    assert_eq!(uf, UnionFind::with_capacity(8));
+}
+
+#[test]
+fn test_try_get() {
+    let mut v = UnionFind::<u32>::with_capacity(5);
+    v.union_chain(&[0, 1, 2, 3]);
+    assert_eq!(v.try_get(0), Some(v.get(0)));
+    assert_eq!(v.try_get(4), Some(4));
+    assert_eq!(v.try_get(5), None);
+    // 0 -> 1 -> 0, with 2 leading into the cycle.
+    v.ptrs[0].set(1);
+    v.ptrs[1].set(0);
+    v.ptrs[2].set(0);
+    assert_eq!(v.try_get(0), None);
+    assert_eq!(v.try_get(2), None);
+    assert_eq!(v.try_get(4), Some(4));
+    v.ptrs[3].set(7);
+    assert_eq!(v.try_get(3), None);
+}
+
+#[test]
+fn test_shrink_to_fit() {
+    let mut v = UnionFind::<u32>::with_capacity(16);
+    assert_eq!(v.heap_bytes(), 16 * 4 * 2);
+    v.ptrs.reserve(1000);
+    v.extend_by(4);
+    v.union_chain(&[0, 5, 17]);
+    let before = v.heap_bytes();
+    v.shrink_to_fit();
+    assert!(v.heap_bytes() < before);
+    assert_eq!(v.heap_bytes(), 20 * 4 * 2);
+    assert_eq!(v.curr_len(), 18);
+}
+
+#[test]
+fn test_largest_component() {
+    assert_eq!(UnionFind::new(0).largest_component(), None);
+    let mut v = UnionFind::<u32>::with_capacity(9);
+    v.union_chain(&[0, 1, 2]);
+    v.union_chain(&[4, 5, 6, 7, 8]);
+    assert_eq!(v.largest_component(), Some((v.get(4), 5)));
+    let mut u = UnionFind::new(4);
+    u.set(3, 2);
+    u.set(0, 1);
+    assert_eq!(u.largest_component(), Some((1, 2)));
+}
+
+#[test]
+fn test_union_all() {
+    let pairs = [(0, 1), (1, 2), (2, 0), (3, 4), (4, 3), (5, 5)];
+    let mut v = UnionFind::new(7);
+    let mut u = UnionFind::<u32>::with_capacity(7);
+    assert_eq!(v.union_all(&pairs), 3);
+    assert_eq!(u.union_all(&pairs), 3);
+    assert_eq!(v.curr_len(), 4);
+    assert_eq!(u.curr_len(), 4);
+    assert_eq!(v.union_all(&pairs), 0);
+}
+
+#[test]
+fn test_make_singleton() {
+    let mut v = UnionFind::<u32>::with_capacity(5);
+    // 0 -> 1 -> 2, 3 -> 2
+    v.set(0, 1);
+    v.set(1, 2);
+    v.set(3, 2);
+    v.ptrs[0].set(1);
+    assert!(v.make_singleton(3));
+    assert_eq!(v.curr_len(), 3);
+    assert!(v.is_root(3));
+    assert_eq!(v.size_of(3), 1);
+    assert_eq!(v.size_of(0), 3);
+
+    let before = v.clone();
+    // 1 has 0 pointing at it, and 2 is a root.
+    assert!(!v.make_singleton(1));
+    assert!(!v.make_singleton(2));
+    assert!(!v.make_singleton(4));
+    assert_eq!(v, before);
+    assert!(v.make_singleton(0));
+    assert!(v.make_singleton(1));
+    assert_eq!(v.curr_len(), 5);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "stale component count")]
+fn test_validate_stale_len() {
+    let mut v = UnionFind::new(4);
+    v.set(0, 1);
+    assert_eq!(v.num_components_recount(), 3);
+    v.len += 1;
+    v.validate();
+}
+
+#[test]
+fn test_find_no_compress() {
+    fn roots<U: UnionFindOp>(uf: &U) -> Vec<usize> {
+        (0..uf.capacity()).map(|i| uf.find_no_compress(i)).collect()
+    }
+    let mut v = UnionFind::<u32>::with_capacity(6);
+    v.union_chain(&[0, 1, 2, 3]);
+    // 0 -> 1 -> 2 -> 3
+    v.ptrs[0].set(1);
+    v.ptrs[1].set(2);
+    let before = v.clone();
+    assert_eq!(roots(&v), vec![3, 3, 3, 3, 4, 5]);
+    assert_eq!(v.ptrs, before.ptrs);
+    assert_eq!(v.find(0), 3);
+    assert_ne!(v.ptrs, before.ptrs);
+
+    {
+        let a = AtomicUnionFind::new(3);
+        a.union_shared(0, 1);
+        a.union_shared(1, 2);
+        assert_eq!(roots(&a), vec![2, 2, 2]);
+    }
+}
+
+#[test]
+fn test_extend_by_range() {
+    let mut v = UnionFind::new(3);
+    let mut u = UnionFind::<u32>::with_capacity(3);
+    v.set(0, 1);
+    u.set(0, 1);
+    let old = v.capacity();
+    let r = v.extend_by(4);
+    assert_eq!(r, old..old + 4);
+    assert_eq!(u.extend_by(4), 3..7);
+    assert!(r.clone().all(|i| v.is_root(i) && v.size_of(i) == 1));
+    assert!(r.clone().all(|i| u.is_root(i) && u.size_of(i) == 1));
+    assert_eq!(v.curr_len(), 6);
+    assert_eq!(v.extend_by(0), 7..7);
+}
+
+#[test]
+fn test_len_as_index_type() {
+    assert!(size_of::<UnionFind<u32>>() < size_of::<UnionFind<usize>>());
+    // Smaller than storing the count as a `usize` alongside the vectors and flag.
+    assert!(size_of::<UnionFind<u32>>() < 2 * size_of::<Vec<u32>>() + size_of::<usize>() + 1);
+    let mut v = UnionFind::new_u8(200);
+    v.union_chain(&(0..100).collect::<Vec<_>>());
+    assert_eq!(v.curr_len(), 101);
+    v.extend_by(54);
+    assert_eq!(v.curr_len(), 155);
+}
+
+#[test]
+fn test_reserve() {
+    let mut v = UnionFind::<u32>::with_capacity(4);
+    v.set(0, 1);
+    v.reserve(100);
+    let bytes = v.heap_bytes();
+    assert!(v.ptrs.capacity() >= 104);
+    assert_eq!(v.curr_len(), 3);
+    v.extend_by(60);
+    v.extend_by(40);
+    assert_eq!(v.heap_bytes(), bytes);
+    assert_eq!(v.curr_len(), 103);
+}
+
+#[test]
+fn test_clone_into() {
+    let mut src = UnionFind::<u32>::with_capacity(10);
+    src.union_chain(&[1, 4, 7]);
+    let mut dst = UnionFind::<u32>::with_capacity(16);
+    let bytes = dst.heap_bytes();
+    src.clone_into(&mut dst);
+    assert_eq!(dst.heap_bytes(), bytes);
+    assert_eq!(dst, src);
+
+    let mut dst = UnionFind::new(0);
+    UnionFind::new(3).clone_into(&mut dst);
+    assert_eq!(dst, UnionFind::new(3));
+}
+
+#[test]
+fn test_union_roots() {
+    let mut a = UnionFind::<u32>::with_capacity(6);
+    let mut b = a.clone();
+    for (x, y) in [(0, 1), (2, 3), (1, 3), (4, 4)] {
+        let (rx, ry) = (b.get(x), b.get(y));
+        assert_eq!(a.set(x, y), b.union_roots(rx, ry));
+    }
+    assert_eq!(a, b);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "is not a root")]
+fn test_union_roots_non_root() {
+    let mut v = UnionFind::new(3);
+    v.set(0, 1);
+    v.union_roots(0, 2);
+}
+
+#[test]
+fn test_components_count_after() {
+    let mut v = UnionFind::<u32>::with_capacity(10);
+    v.union_chain(&[0, 1, 2]);
+    v.set(5, 6);
+    let before = v.parents_slice().to_vec();
+    let pairs = [(2, 3), (3, 0), (6, 9), (7, 8), (8, 7)];
+    let predicted = v.components_count_after(&pairs);
+    assert_eq!(v.parents_slice(), before);
+    assert_eq!(v.curr_len(), 7);
+    v.union_all(&pairs);
+    assert_eq!(v.curr_len(), predicted);
+    assert_eq!(predicted, 4);
+}
+
+#[test]
+fn test_growable() {
+    let mut v = UnionFind::new(2).with_growth(true);
+    assert!(v.union(0, 1000));
+    assert_eq!(v.capacity(), 1001);
+    assert!(v.same_set(0, 1000));
+    assert_eq!(v.curr_len(), 1000);
+
+    let mut v = UnionFind::new_growable();
+    v.set(3, 1);
+    assert_eq!((v.capacity(), v.curr_len()), (4, 3));
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_not_growable() {
+    UnionFind::new(2).set(0, 2);
+}
+
+#[test]
+fn test_merge_groups() {
+    let mut v = UnionFind::<u32>::with_capacity(8);
+    assert_eq!(
+        v.merge_groups([vec![1, 4, 7], vec![2, 3], vec![], vec![5]]),
+        3
+    );
+    assert_eq!(v.curr_len(), 5);
+    assert!(v.connected(1, 4) && v.connected(4, 7) && v.connected(2, 3));
+    assert!(!v.connected(1, 2));
+    assert_eq!(v.merge_groups([[7, 1]]), 0);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "escapes subset")]
+fn test_subset_escaping_pointer() {
+    let mut v = UnionFind::<u32>::with_capacity(8);
+    v.set(5, 2);
+    v.subset(4..8);
+}
+
+#[test]
+fn test_try_extend_by() {
+    let mut v = UnionFind::<u8>::with_capacity(250);
+    v.set(0, 1);
+    let before = v.clone();
+    assert_eq!(
+        v.try_extend_by(5),
+        Err(CapacityError::Overflow {
+            requested: 255,
+            max: 254
+        })
+    );
+    assert!(v.try_extend_by(usize::MAX).is_err());
+    assert_eq!(v, before);
+    assert_eq!(v.try_extend_by(4), Ok(250..254));
+    assert_eq!((v.capacity(), v.curr_len()), (254, 253));
+}
+
+#[test]
+#[should_panic(expected = "will overflow")]
+fn test_extend_by_overflow() {
+    UnionFind::<u8>::with_capacity(250).extend_by(5);
+}
+
+#[test]
+fn test_depth() {
+    let mut v = UnionFind::from_parents(vec![1, 2, 3, 4, 5, 5, 6]).unwrap();
+    assert_eq!(v.depth(0), 5);
+    assert_eq!(v.depth(5), 0);
+    assert_eq!(v.max_depth(), 5);
+    v.compress();
+    assert_eq!(v.max_depth(), 1);
+    assert_eq!(v.depth(6), 0);
+    assert_eq!(UnionFind::new(0).max_depth(), 0);
+}
+
+#[test]
+fn test_split_at() {
+    let mut v = UnionFind::<u32>::with_capacity(10);
+    v.union_all(&[(0, 3), (3, 1), (5, 9), (7, 6), (8, 9)]);
+    let total = v.curr_len();
+    let (l, r) = v.clone().split_at(5);
+    l.validate();
+    r.validate();
+    assert_eq!(l.curr_len() + r.curr_len(), total);
+    assert_eq!((l.capacity(), r.capacity()), (5, 5));
+    assert!(l.same_partition(&v.subset_clone(0..5)));
+    assert!(r.same_partition(&v.subset_clone(5..10)));
+    assert_eq!(r.size_of(0), 3);
+
+    let mut joined = l;
+    joined.append(&r);
+    assert!(joined.same_partition(&v));
+}
+
+#[test]
+#[should_panic(expected = "straddles")]
+fn test_split_at_straddling() {
+    let mut v = UnionFind::<u32>::with_capacity(4);
+    v.set(0, 3);
+    v.split_at(2);
+}
+
+#[test]
+fn test_compact() {
+    let mut v = UnionFind::<u32>::with_capacity(1000);
+    for i in 3..1000 {
+        v.set(i, i % 3);
+    }
+    let before = v.clone();
+    let map = v.compact();
+    assert_eq!((v.capacity(), v.curr_len()), (3, 3));
+    assert!((0..3).all(|i| v.is_root(i)));
+    assert_eq!(map.len(), 1000);
+    for i in 0..1000 {
+        assert_eq!(map[i], map[before.get(i)]);
+        assert_eq!(map[i] == map[0], before.connected(i, 0));
+    }
+    assert_eq!(&map[..3], [0, 1, 2]);
+    assert!(v.heap_bytes() < before.heap_bytes());
+}
+
+#[test]
+fn test_set_with() {
+    let weights = [5, 1, 7, 2, 9, 4, 3, 8];
+    let mut sums = weights.to_vec();
+    let mut v = UnionFind::new(8);
+    let mut calls = 0;
+    for (a, b) in [(0, 1), (2, 3), (1, 0), (3, 5), (6, 6), (7, 0), (5, 7)] {
+        v.set_with(a, b, |keep, absorbed| {
+            sums[keep] += sums[absorbed];
+            calls += 1;
+        });
+    }
+    assert_eq!(calls, 8 - v.curr_len());
+    for r in v.roots() {
+        let brute = (0..8)
+            .filter(|&i| v.get(i) == r)
+            .map(|i| weights[i])
+            .sum::<i32>();
+        assert_eq!(sums[r], brute);
+    }
+}
+
+#[test]
+fn test_try_reserve() {
+    let mut v = UnionFind::new(4);
+    v.set(0, 1);
+    let before = v.clone();
+    assert!(v.try_reserve(usize::MAX).is_err());
+    assert!(matches!(
+        v.try_extend_by(usize::MAX / 2),
+        Err(CapacityError::Alloc(_))
+    ));
+    assert_eq!(v, before);
+    assert_eq!((v.capacity(), v.curr_len()), (4, 3));
+    assert!(v.try_reserve(16).is_ok());
+    assert_eq!(v.try_extend_by(2), Ok(4..6));
+}
+
+#[test]
+fn test_component_sizes() {
+    let mut v = UnionFind::new(10);
+    let mut u = UnionFind::<u32>::with_capacity(10);
+    for (a, b) in [(0, 9), (3, 9), (4, 5), (7, 7)] {
+        v.set(a, b);
+        u.set(a, b);
+    }
+    let sizes = v.component_sizes().collect::<Vec<_>>();
+    assert_eq!(sizes.len(), v.curr_len());
+    assert_eq!(sizes.iter().map(|s| s.1).sum::<usize>(), v.capacity());
+    assert!(sizes.contains(&(9, 3)) && sizes.contains(&(5, 2)));
+    assert_eq!(u.component_sizes().collect::<Vec<_>>(), sizes);
+}
+
+#[cfg(not(feature = "unchecked"))]
+#[test]
+#[should_panic]
+fn test_borrowed_out_of_range() {
+    let mut v = UnionFind::<u32>::with_capacity(8);
+    let s = v.subset(4..8);
+    s.get_compress(4);
+}
+
+#[test]
+fn test_count_singletons() {
+    let mut v = UnionFind::new(6);
+    let mut u = UnionFind::<u32>::with_capacity(6);
+    v.union_chain(&[1, 3, 5]);
+    u.union_chain(&[1, 3, 5]);
+    assert_eq!((v.count_singletons(), v.count_nontrivial()), (3, 1));
+    assert_eq!((u.count_singletons(), u.count_nontrivial()), (3, 1));
+    let merged = v.capacity() - v.count_singletons();
+    assert_eq!(merged, 3);
+    assert_eq!(v.count_singletons() + v.count_nontrivial(), v.curr_len());
+    assert_eq!(UnionFind::new(0).count_nontrivial(), 0);
+}
+
+#[test]
+fn test_small_component_roots() {
+    let mut v = UnionFind::<u32>::with_capacity(8);
+    v.set(1, 2);
+    v.union_chain(&[3, 4, 5, 6, 7]);
+    assert_eq!(v.small_component_roots(3), [0, 2]);
+    assert_eq!(v.small_component_roots(1), [] as [usize; 0]);
+    assert_eq!(v.small_component_roots(6).len(), v.curr_len());
+}
+
+#[test]
+fn test_set_by_min() {
+    let pairs = [(4, 2), (2, 0), (5, 3), (1, 3), (6, 5)];
+    let mut a = UnionFind::<u32>::with_capacity(8);
+    let mut b = UnionFind::<u32>::with_capacity(8);
+    for &(x, y) in &pairs {
+        a.set_by_min(x, y);
+        b.set_by_min(y, x);
+    }
+    assert_eq!(a.ptrs, b.ptrs);
+    assert!((0..8).all(|i| a.get(i) == [0, 1, 0, 1, 0, 1, 1, 7][i]));
+
+    let mut c = UnionFind::<u32>::with_capacity(8);
+    for &(x, y) in pairs.iter().rev() {
+        c.set_by_min(x, y);
+    }
+    // Intermediate links depend on order, but every root is the same.
+    a.compress();
+    c.compress();
+    assert_eq!(a.ptrs, c.ptrs);
+}
+
+#[test]
+fn test_parent() {
+    let mut v = UnionFind::<u32>::with_capacity(4);
+    // 0 -> 1 -> 2 -> 3
+    for i in 0..3 {
+        v.set_parent_raw(i, i + 1);
+    }
+    assert_eq!((v.parent(0), v.get(0)), (1, 3));
+    assert_eq!((v.parent(1), v.get(1)), (2, 3));
+    assert_eq!((v.parent(3), v.get(3)), (3, 3));
+    assert_eq!(v.curr_len(), 4);
+    assert!(v.repair_len());
+    assert_eq!(v.curr_len(), 1);
+    // `repair_len` compresses through `find`.
+    assert_eq!(v.parent(0), 3);
+}
+
+#[test]
+fn test_find_range() {
+    let mut v = UnionFind::new(12);
+    let mut u = UnionFind::<u32>::with_capacity(12);
+    // 0 -> 1 -> ... -> 9
+    for i in 0..9 {
+        v.set_parent_raw(i, i + 1);
+        u.set_parent_raw(i, i + 1);
+    }
+    assert_eq!(v.find_range(0..10), vec![9; 10]);
+    assert_eq!(u.find_range(0..10), vec![9; 10]);
+    assert!((0..10).all(|i| v.parent(i) == 9 && u.parent(i) == 9));
+    assert_eq!(v.find_range(10..12), vec![10, 11]);
+    assert!(v.find_range(3..3).is_empty());
+}
+
+#[test]
+fn test_is_root_does_not_compress() {
+    fn roots(uf: &dyn UnionFindOp) -> Vec<bool> {
+        (0..uf.capacity()).map(|i| uf.is_root(i)).collect()
+    }
+    let mut uf = UnionFind::<u32>::with_capacity(4);
+    uf.union_chain(&[0, 1, 2, 3]);
+    let parents = (0..4).map(|i| uf.parent(i)).collect::<Vec<_>>();
+    assert_eq!(roots(&uf), [false, false, false, true]);
+    assert_eq!((0..4).map(|i| uf.parent(i)).collect::<Vec<_>>(), parents);
+
+    {
+        let atomic = AtomicUnionFind::from(uf.clone());
+        assert_eq!(roots(&atomic), [false, false, false, true]);
+        let atomic = UnionFind::<u32>::from(atomic);
+        assert_eq!(
+            (0..4).map(|i| atomic.parent(i)).collect::<Vec<_>>(),
+            parents
+        );
+    }
+
+    let b = uf.subset(0..4);
+    assert_eq!(roots(&b), [false, false, false, true]);
+    b.validate();
+    assert_eq!((0..4).map(|i| uf.parent(i)).collect::<Vec<_>>(), parents);
+}
+
+#[test]
+fn test_get_unrolled() {
+    for n in [1, 2, 3, 10, 11] {
+        let v = UnionFind::from_parents((1..n as u32).chain([n as u32 - 1]).collect()).unwrap();
+        assert!((0..n).all(|i| v.get_unrolled(i) == v.get(i)));
+    }
+    let mut v = UnionFind::<u32>::with_capacity(10);
+    v.union_all(&[(0, 5), (2, 5), (5, 9), (1, 3), (7, 1)]);
+    assert!((0..10).all(|i| v.get_unrolled(i) == v.get(i)));
+}
+
+/// Compares `get` and `get_unrolled` on chains, run with
+/// `cargo test --release bench_get -- --ignored --nocapture`.
+#[cfg(feature = "std")]
+#[test]
+#[ignore]
+fn bench_get() {
+    use std::time::Instant;
+    for n in [1_000, 10_000, 100_000, 1_000_000] {
+        // visit the elements in a scattered order, so that each hop is likely a cache miss.
+        let order = (0..n as u64)
+            .map(|i| (i * 0x9E37_79B9 % n as u64) as u32)
+            .collect::<Vec<_>>();
+        let mut parents = vec![0; n];
+        for w in order.windows(2) {
+            parents[w[0] as usize] = w[1];
+        }
+        let last = order[n - 1];
+        parents[last as usize] = last;
+        let v = UnionFind::from_parents(parents).unwrap();
+        let reps = 100_000_000 / n;
+        let time = |f: &dyn Fn(usize) -> usize| {
+            let start = Instant::now();
+            for i in 0..reps {
+                assert_eq!(
+                    core::hint::black_box(f(order[i % 8] as usize)),
+                    last as usize
+                );
+            }
+            start.elapsed() / reps as u32
+        };
+        let plain = time(&|i| v.get(core::hint::black_box(i)));
+        let unrolled = time(&|i| v.get_unrolled(core::hint::black_box(i)));
+        std::println!("chain of {n}: get {plain:?}, get_unrolled {unrolled:?}");
+    }
+}
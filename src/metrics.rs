@@ -0,0 +1,134 @@
+use super::{UnionFind, UnionFindOp};
+use std::collections::HashMap;
+
+/// Number of unordered pairs among `n` elements.
+fn pairs(n: usize) -> f64 {
+    (n * n.saturating_sub(1) / 2) as f64
+}
+
+/// Counts of elements shared between each pair of components of two partitions.
+struct Contingency {
+    /// Number of elements in both component `a` and component `b`, keyed by `(a, b)`.
+    cells: HashMap<(usize, usize), usize>,
+    /// Size of each component in the first partition.
+    rows: HashMap<usize, usize>,
+    /// Size of each component in the second partition.
+    cols: HashMap<usize, usize>,
+    n: usize,
+}
+
+impl<T: Copy + Eq> UnionFind<T>
+where
+    Self: UnionFindOp,
+{
+    fn contingency(&self, other: &Self) -> Contingency {
+        let n = self.capacity();
+        assert_eq!(n, other.capacity());
+        let mut c = Contingency {
+            cells: HashMap::new(),
+            rows: HashMap::new(),
+            cols: HashMap::new(),
+            n,
+        };
+        for i in 0..n {
+            let (a, b) = (self.find(i), other.find(i));
+            *c.cells.entry((a, b)).or_default() += 1;
+            *c.rows.entry(a).or_default() += 1;
+            *c.cols.entry(b).or_default() += 1;
+        }
+        c
+    }
+    /// Fraction of element pairs which are either together in both partitions or apart in both
+    /// partitions. Both must have the same capacity.
+    pub fn rand_index(&self, other: &Self) -> f64 {
+        let c = self.contingency(other);
+        let total = pairs(c.n);
+        if total == 0. {
+            return 1.;
+        }
+        let both = c.cells.values().map(|&v| pairs(v)).sum::<f64>();
+        let rows = c.rows.values().map(|&v| pairs(v)).sum::<f64>();
+        let cols = c.cols.values().map(|&v| pairs(v)).sum::<f64>();
+        (total + 2. * both - rows - cols) / total
+    }
+    /// Rand index adjusted for chance. Returns 1 if both partitions are trivially identical, such
+    /// as when both are a single component or both are all singletons.
+    pub fn adjusted_rand_index(&self, other: &Self) -> f64 {
+        let c = self.contingency(other);
+        let total = pairs(c.n);
+        let index = c.cells.values().map(|&v| pairs(v)).sum::<f64>();
+        let rows = c.rows.values().map(|&v| pairs(v)).sum::<f64>();
+        let cols = c.cols.values().map(|&v| pairs(v)).sum::<f64>();
+        if total == 0. {
+            return 1.;
+        }
+        let expected = rows * cols / total;
+        let max = (rows + cols) / 2.;
+        if max == expected {
+            return 1.;
+        }
+        (index - expected) / (max - expected)
+    }
+    /// Mutual information of both partitions, normalized by the mean of their entropies. Returns
+    /// 1 if both partitions have zero entropy, i.e. are a single component.
+    pub fn nmi(&self, other: &Self) -> f64 {
+        let c = self.contingency(other);
+        let n = c.n as f64;
+        let entropy = |m: &HashMap<usize, usize>| {
+            -m.values()
+                .map(|&v| v as f64 / n)
+                .map(|p| p * p.ln())
+                .sum::<f64>()
+        };
+        let denom = (entropy(&c.rows) + entropy(&c.cols)) / 2.;
+        if denom == 0. {
+            return 1.;
+        }
+        let mi = c
+            .cells
+            .iter()
+            .map(|(&(a, b), &v)| {
+                let v = v as f64;
+                v / n * (n * v / (c.rows[&a] * c.cols[&b]) as f64).ln()
+            })
+            .sum::<f64>();
+        mi / denom
+    }
+}
+
+#[test]
+fn test_rand_index() {
+    // {0, 1, 2}, {3, 4} vs {0, 1}, {2, 3, 4}
+    let mut a = UnionFind::new(5);
+    a.union_chain(&[0, 1, 2]);
+    a.union(3, 4);
+    let mut b = UnionFind::new(5);
+    b.union(0, 1);
+    b.union_chain(&[2, 3, 4]);
+    // disagreeing pairs are (0, 2), (1, 2), (2, 3), (2, 4)
+    assert!((a.rand_index(&b) - 0.6).abs() < 1e-12);
+    assert_eq!(a.rand_index(&a), 1.);
+}
+
+#[test]
+fn test_adjusted_rand_index_nmi() {
+    let mut a = UnionFind::new(5);
+    a.union_chain(&[0, 1, 2]);
+    a.union(3, 4);
+    let mut b = UnionFind::new(5);
+    b.union(0, 1);
+    b.union_chain(&[2, 3, 4]);
+    assert!((a.adjusted_rand_index(&b) - 1. / 6.).abs() < 1e-12);
+    assert!((a.nmi(&b) - 0.4325380677663126).abs() < 1e-12);
+    assert!((a.adjusted_rand_index(&a) - 1.).abs() < 1e-12);
+    assert!((a.nmi(&a) - 1.).abs() < 1e-12);
+
+    let singletons = UnionFind::new(5);
+    let mut single = UnionFind::new(5);
+    single.union_chain(&[0, 1, 2, 3, 4]);
+    assert_eq!(singletons.adjusted_rand_index(&singletons), 1.);
+    assert_eq!(single.adjusted_rand_index(&single), 1.);
+    assert_eq!(single.nmi(&single), 1.);
+    assert_eq!(single.adjusted_rand_index(&singletons), 0.);
+    assert_eq!(single.nmi(&singletons), 0.);
+}
@@ -0,0 +1,179 @@
+use super::{AtomicUnionFind, UnionFind, UnionFindOp};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use rayon::prelude::*;
+
+/// Follows parent pointers to the root without compressing.
+#[inline]
+fn root_of(parents: &[u32], mut v: usize) -> usize {
+    while let n = parents[v] as usize
+        && n != v
+    {
+        v = n;
+    }
+    v
+}
+
+impl UnionFind<u32> {
+    /// Parallel version of [`Self::groups_into`], producing identical output. This takes
+    /// `&mut self` since the cells cannot be shared across threads, so roots are resolved from
+    /// [`Self::parents_slice`].
+    pub fn par_groups_into(&mut self, perm: &mut Vec<usize>, offsets: &mut Vec<usize>) {
+        let n = self.capacity();
+        let parents = self.parents_slice();
+        let roots = (0..n)
+            .into_par_iter()
+            .map(|i| root_of(parents, i))
+            .collect::<Vec<_>>();
+
+        let counts = (0..n).map(|_| AtomicUsize::new(0)).collect::<Vec<_>>();
+        let first = (0..n)
+            .map(|_| AtomicUsize::new(usize::MAX))
+            .collect::<Vec<_>>();
+        roots.par_iter().enumerate().for_each(|(i, &r)| {
+            counts[r].fetch_add(1, Ordering::Relaxed);
+            first[r].fetch_min(i, Ordering::Relaxed);
+        });
+        let counts = counts
+            .into_iter()
+            .map(AtomicUsize::into_inner)
+            .collect::<Vec<_>>();
+        let first = first
+            .into_iter()
+            .map(AtomicUsize::into_inner)
+            .collect::<Vec<_>>();
+
+        // order components by first appearance, to match `groups_into`.
+        let mut comps = (0..n)
+            .into_par_iter()
+            .filter(|&r| counts[r] > 0)
+            .collect::<Vec<_>>();
+        comps.par_sort_unstable_by_key(|&r| first[r]);
+
+        offsets.clear();
+        offsets.push(0);
+        let cursor = (0..n).map(|_| AtomicUsize::new(0)).collect::<Vec<_>>();
+        for &r in &comps {
+            let start = *offsets.last().unwrap();
+            cursor[r].store(start, Ordering::Relaxed);
+            offsets.push(start + counts[r]);
+        }
+
+        let slots = (0..n).map(|_| AtomicUsize::new(0)).collect::<Vec<_>>();
+        roots.par_iter().enumerate().for_each(|(i, &r)| {
+            let at = cursor[r].fetch_add(1, Ordering::Relaxed);
+            slots[at].store(i, Ordering::Relaxed);
+        });
+        perm.clear();
+        perm.par_extend(slots.into_par_iter().map(AtomicUsize::into_inner));
+
+        // scattering is unordered, so sort each component's members.
+        let mut segments = Vec::with_capacity(comps.len());
+        let mut rest = perm.as_mut_slice();
+        for w in offsets.windows(2) {
+            let (seg, tail) = rest.split_at_mut(w[1] - w[0]);
+            segments.push(seg);
+            rest = tail;
+        }
+        segments.into_par_iter().for_each(|s| s.sort_unstable());
+    }
+    /// Parallel version of [`Self::compress`]. All roots are resolved in parallel before any
+    /// pointer is written back, so the result is identical.
+    pub fn par_compress(&mut self) {
+        let parents = self.parents_slice();
+        let roots = (0..parents.len())
+            .into_par_iter()
+            .map(|i| root_of(parents, i) as u32)
+            .collect::<Vec<_>>();
+        for (p, r) in self.ptrs.iter().zip(roots) {
+            p.set(r);
+        }
+    }
+}
+
+impl AtomicUnionFind {
+    /// Unions every pair in parallel with [`Self::union_shared`]. The resulting components are
+    /// the same as unioning the pairs sequentially, though the roots may differ.
+    pub fn par_union_all(&self, pairs: &[(usize, usize)]) {
+        pairs.par_iter().for_each(|&(a, b)| {
+            self.union_shared(a, b);
+        });
+    }
+    /// Points every element directly at its root in parallel. This may also run concurrently
+    /// with [`Self::union_shared`], in which case some elements may not end up fully flattened.
+    pub fn par_compress(&self) {
+        (0..self.capacity()).into_par_iter().for_each(|i| {
+            self.get_compress(i);
+        });
+    }
+}
+
+#[test]
+fn test_par_groups_into() {
+    let mut uf = UnionFind::<u32>::with_capacity(1000);
+    for i in 0..1000 {
+        if i % 7 != 0 {
+            uf.set(i, (i * 31) % 1000);
+        }
+    }
+    let (mut perm, mut offsets) = (vec![], vec![]);
+    uf.groups_into(&mut perm, &mut offsets);
+    let (mut par_perm, mut par_offsets) = (vec![], vec![]);
+    uf.par_groups_into(&mut par_perm, &mut par_offsets);
+    assert_eq!(offsets, par_offsets);
+    assert_eq!(perm, par_perm);
+}
+
+#[test]
+fn test_par_union_all() {
+    // a 40x40 grid, with horizontal edges on even rows and vertical edges every third column.
+    let w = 40;
+    let mut edges = vec![];
+    for y in 0..w {
+        for x in 0..w {
+            let i = y * w + x;
+            if x + 1 < w && y % 2 == 0 {
+                edges.push((i, i + 1));
+            }
+            if y + 1 < w && x % 3 == 0 {
+                edges.push((i, i + w));
+            }
+        }
+    }
+    let mut seq = UnionFind::new(w * w);
+    seq.union_all(&edges);
+    for threads in [1, 4] {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        let uf = AtomicUnionFind::new(w * w);
+        pool.install(|| uf.par_union_all(&edges));
+        assert_eq!(uf.len(), seq.curr_len());
+        let uf: UnionFind<u32> = uf.into();
+        assert!(uf.same_partition(&UnionFind::<u32>::from_edges(w * w, edges.iter().copied())));
+    }
+}
+
+#[test]
+fn test_par_compress() {
+    let n = 2000;
+    let mut uf = UnionFind::<u32>::with_capacity(n);
+    let mut state = 7u64;
+    for _ in 0..n {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+        let a = (state >> 33) as usize % n;
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+        let b = (state >> 33) as usize % n;
+        uf.set(a, b);
+    }
+    let atomic = AtomicUnionFind::from(uf.clone());
+    let mut par = uf.clone();
+    uf.compress();
+    par.par_compress();
+    assert!((0..n).all(|i| uf.get(i) == par.get(i)));
+    assert_eq!(uf.parents_slice(), par.parents_slice());
+
+    atomic.par_compress();
+    let atomic: UnionFind<u32> = atomic.into();
+    assert!((0..n).all(|i| atomic.get(i) == uf.get(i)));
+}
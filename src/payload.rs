@@ -0,0 +1,94 @@
+use super::{UnionFind, UnionFindOp};
+use alloc::vec::Vec;
+
+/// A union-find which stores an arbitrary payload per component, combining the payloads of two
+/// components with `merge` when they are unioned.
+#[derive(Debug, Clone)]
+pub struct PayloadUnionFind<P, F: Fn(P, P) -> P> {
+    uf: UnionFind<u32>,
+    /// Payload of each component, only present at roots.
+    payloads: Vec<Option<P>>,
+    merge: F,
+}
+
+impl<P, F: Fn(P, P) -> P> PayloadUnionFind<P, F> {
+    /// Creates a set of singletons without any payloads.
+    pub fn new(len: usize, merge: F) -> Self {
+        Self {
+            uf: UnionFind::<u32>::with_capacity(len),
+            payloads: (0..len).map(|_| None).collect(),
+            merge,
+        }
+    }
+    /// Unions the components of `v` and `to`, merging their payloads at the surviving root. If
+    /// only one component has a payload, it is kept as is.
+    pub fn set(&mut self, v: usize, to: usize) -> bool {
+        let root_v = self.uf.find(v);
+        let root_to = self.uf.find(to);
+        if root_v == root_to {
+            return false;
+        }
+        self.uf.union(root_v, root_to);
+        let root = self.uf.find(root_to);
+        let a = self.payloads[root_v].take();
+        let b = self.payloads[root_to].take();
+        self.payloads[root] = match (a, b) {
+            (Some(a), Some(b)) => Some((self.merge)(a, b)),
+            (a, b) => a.or(b),
+        };
+        true
+    }
+    /// The payload of the component containing `v`.
+    pub fn payload(&self, v: usize) -> Option<&P> {
+        self.payloads[self.uf.find(v)].as_ref()
+    }
+    /// Replaces the payload of the component containing `v`.
+    pub fn set_payload(&mut self, v: usize, p: P) {
+        let r = self.uf.find(v);
+        self.payloads[r] = Some(p);
+    }
+    /// The underlying union-find.
+    pub fn inner(&self) -> &UnionFind<u32> {
+        &self.uf
+    }
+}
+
+impl<P, F: Fn(P, P) -> P> UnionFindOp for PayloadUnionFind<P, F> {
+    fn find(&self, v: usize) -> usize {
+        self.uf.find(v)
+    }
+    fn find_no_compress(&self, v: usize) -> usize {
+        self.uf.get(v)
+    }
+    fn is_root(&self, v: usize) -> bool {
+        self.uf.is_root(v)
+    }
+    fn union(&mut self, v: usize, to: usize) -> bool {
+        self.set(v, to)
+    }
+    fn len(&self) -> usize {
+        self.uf.len()
+    }
+    fn capacity(&self) -> usize {
+        self.uf.capacity()
+    }
+}
+
+#[test]
+fn test_payload() {
+    let mut uf = PayloadUnionFind::new(5, |mut a: Vec<u32>, b| {
+        a.extend(b);
+        a
+    });
+    for i in 0..4 {
+        uf.set_payload(i, vec![i as u32]);
+    }
+    uf.set(0, 1);
+    assert_eq!(uf.payload(1), Some(&vec![0, 1]));
+    uf.set(3, 2);
+    uf.set(2, 0);
+    assert_eq!(uf.payload(0), Some(&vec![3, 2, 0, 1]));
+    assert_eq!(uf.payload(4), None);
+    uf.set(4, 3);
+    assert_eq!(uf.payload(4).unwrap().len(), 4);
+}
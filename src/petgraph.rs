@@ -0,0 +1,45 @@
+use super::UnionFind;
+use alloc::vec::Vec;
+
+/// A thin wrapper exposing the same method names as `petgraph::unionfind::UnionFind`, to ease
+/// migrating from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PetgraphUnionFind(UnionFind<usize>);
+
+impl PetgraphUnionFind {
+    pub fn new(n: usize) -> Self {
+        Self(UnionFind::new(n))
+    }
+    /// Returns the representative of `x`, without compressing.
+    pub fn find(&self, x: usize) -> usize {
+        self.0.get(x)
+    }
+    /// Returns the representative of `x`, compressing its path.
+    pub fn find_mut(&mut self, x: usize) -> usize {
+        self.0.get_compress(x)
+    }
+    /// Checks whether `x` and `y` have the same representative.
+    pub fn equiv(&self, x: usize, y: usize) -> bool {
+        self.find(x) == self.find(y)
+    }
+    /// Unions `x` and `y`, returning false if they were already in the same set.
+    pub fn union(&mut self, x: usize, y: usize) -> bool {
+        self.0.set(x, y)
+    }
+    /// Returns the representative of every element.
+    pub fn into_labeling(self) -> Vec<usize> {
+        (0..self.0.capacity()).map(|i| self.0.get(i)).collect()
+    }
+}
+
+#[test]
+fn test_petgraph_compat() {
+    let mut uf = PetgraphUnionFind::new(5);
+    assert!(uf.union(0, 1));
+    assert!(uf.union(3, 1));
+    assert!(!uf.union(0, 3));
+    assert!(uf.equiv(0, 3));
+    assert_eq!(uf.find_mut(3), uf.find(0));
+    let labels = uf.into_labeling();
+    assert_eq!(labels, vec![1, 1, 2, 1, 4]);
+}
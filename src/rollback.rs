@@ -0,0 +1,118 @@
+use super::{UnionFind, UnionFindOp};
+use alloc::{vec, vec::Vec};
+
+/// A union-find whose unions can be undone back to a checkpoint. Path compression would lose
+/// the information needed to undo, so `find` never compresses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RollbackUnionFind {
+    uf: UnionFind<u32>,
+    /// Every root which was linked under another root, in order.
+    journal: Vec<u32>,
+}
+
+impl RollbackUnionFind {
+    pub fn new(len: usize) -> Self {
+        Self {
+            uf: UnionFind::<u32>::with_capacity(len),
+            journal: vec![],
+        }
+    }
+    pub fn set(&mut self, v: usize, to: usize) -> bool {
+        let root_to = self.uf.get(to);
+        let root_v = self.uf.get(v);
+        if root_v == root_to {
+            return false;
+        }
+        self.uf.ptrs[root_v].set(root_to as u32);
+        self.uf.sizes[root_to] += self.uf.sizes[root_v];
+        self.uf.len -= 1;
+        self.journal.push(root_v as u32);
+        true
+    }
+    /// Returns a checkpoint which can later be rolled back to.
+    pub fn checkpoint(&self) -> usize {
+        self.journal.len()
+    }
+    /// Undoes every union performed since `cp` was taken.
+    pub fn rollback_to(&mut self, cp: usize) {
+        assert!(cp <= self.journal.len(), "Invalid checkpoint {cp}");
+        for root in self.journal.drain(cp..).rev() {
+            let parent = self.uf.ptrs[root as usize].get() as usize;
+            self.uf.sizes[parent] -= self.uf.sizes[root as usize];
+            self.uf.ptrs[root as usize].set(root);
+            self.uf.len += 1;
+        }
+    }
+    /// Runs `f`, undoing all of its unions if it returns false.
+    pub fn transaction<F: FnOnce(&mut Self) -> bool>(&mut self, f: F) -> bool {
+        let cp = self.checkpoint();
+        let commit = f(self);
+        if !commit {
+            self.rollback_to(cp);
+        }
+        commit
+    }
+    /// Number of elements in the component containing `v`, without compressing.
+    pub fn size_of(&self, v: usize) -> usize {
+        self.uf.size_of(v)
+    }
+}
+
+impl UnionFindOp for RollbackUnionFind {
+    fn find(&self, v: usize) -> usize {
+        self.uf.get(v)
+    }
+    fn is_root(&self, v: usize) -> bool {
+        self.uf.is_root(v)
+    }
+    fn union(&mut self, v: usize, to: usize) -> bool {
+        self.set(v, to)
+    }
+    fn len(&self) -> usize {
+        self.uf.curr_len()
+    }
+    fn capacity(&self) -> usize {
+        self.uf.capacity()
+    }
+}
+
+#[test]
+fn test_transaction() {
+    let mut uf = RollbackUnionFind::new(6);
+    uf.set(0, 1);
+    let before = uf.clone();
+    assert!(!uf.transaction(|uf| {
+        uf.set(1, 2);
+        uf.set(3, 4);
+        assert_eq!(uf.len(), 3);
+        false
+    }));
+    assert_eq!(uf, before);
+
+    assert!(uf.transaction(|uf| {
+        uf.set(4, 5);
+        true
+    }));
+    assert_eq!(uf.len(), 4);
+    assert!(uf.same_set(4, 5));
+}
+
+#[test]
+fn test_rollback_to() {
+    let mut uf = RollbackUnionFind::new(8);
+    uf.set(0, 1);
+    uf.set(2, 3);
+    let cp = uf.checkpoint();
+    let before = uf.clone();
+    uf.set(1, 3);
+    uf.set(4, 5);
+    uf.set(5, 3);
+    // Unioning already joined elements does not add to the journal.
+    uf.set(0, 4);
+    assert_eq!(uf.len(), 3);
+    uf.rollback_to(cp);
+    assert_eq!(uf, before);
+    assert_eq!(uf.len(), 6);
+    assert!(uf.same_set(0, 1) && !uf.same_set(1, 2));
+    assert_eq!(uf.size_of(3), 2);
+}
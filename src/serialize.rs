@@ -0,0 +1,78 @@
+#[cfg(test)]
+use crate::UnionFindOp;
+use crate::{UfIndex, UnionFind};
+use alloc::vec::Vec;
+use core::cell::Cell;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize)]
+#[serde(rename = "UnionFind")]
+struct ReprRef<'a, T> {
+    ptrs: &'a [T],
+    len: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "UnionFind")]
+struct Repr<T> {
+    ptrs: Vec<T>,
+    len: usize,
+}
+
+impl<T: UfIndex + Serialize> Serialize for UnionFind<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let ptrs: Vec<T> = self.ptrs.iter().map(Cell::get).collect();
+        ReprRef {
+            ptrs: &ptrs,
+            len: self.len.to_usize(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T: UfIndex + Deserialize<'de>> Deserialize<'de> for UnionFind<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let Repr { ptrs, len } = Repr::<T>::deserialize(deserializer)?;
+        let uf = Self::try_from_parents(ptrs).map_err(D::Error::custom)?;
+        if uf.len.to_usize() != len {
+            return Err(D::Error::custom(format_args!(
+                "len is {len} but there are {} roots",
+                uf.len.to_usize()
+            )));
+        }
+        Ok(uf)
+    }
+}
+
+#[test]
+fn test_serde_round_trip() {
+    let mut uf = UnionFind::new(10);
+    uf.set(0, 1);
+    uf.set(2, 1);
+    uf.set(5, 7);
+    uf.set(7, 9);
+    let json = serde_json::to_string(&uf).unwrap();
+    let back: UnionFind = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.len(), uf.len());
+    for i in 0..10 {
+        assert_eq!(back.get(i), uf.get(i));
+    }
+
+    let mut uf = UnionFind::<u32>::with_capacity(4);
+    uf.set(3, 0);
+    let back: UnionFind<u32> = serde_json::from_str(&serde_json::to_string(&uf).unwrap()).unwrap();
+    assert!((0..4).all(|i| back.get(i) == uf.get(i)));
+}
+
+#[test]
+fn test_serde_rejects_invalid() {
+    let out_of_bounds = r#"{"ptrs":[0,5],"len":2}"#;
+    assert!(serde_json::from_str::<UnionFind>(out_of_bounds).is_err());
+    let wrong_len = r#"{"ptrs":[0,0,2],"len":3}"#;
+    assert!(serde_json::from_str::<UnionFind>(wrong_len).is_err());
+    let cycle = r#"{"ptrs":[1,0,2],"len":1}"#;
+    assert!(serde_json::from_str::<UnionFind>(cycle).is_err());
+    let ok = r#"{"ptrs":[0,0,2],"len":2}"#;
+    assert_eq!(serde_json::from_str::<UnionFind>(ok).unwrap().len(), 2);
+}
@@ -0,0 +1,219 @@
+use super::{UfIndex, UnionFind, UnionFindOp};
+use alloc::vec::Vec;
+use core::cell::Cell;
+
+/// A union-find which always links the root of the smaller component under the root of the
+/// larger one, keeping trees logarithmically shallow even without compression. Unlike
+/// [`UnionFind::set`], `to`'s root only survives a union if its component is at least as large.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizedUnionFind<T: Copy + Eq = usize> {
+    uf: UnionFind<T>,
+}
+
+/// Returned when a union would create a component larger than allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapExceeded {
+    /// Size of the component which the union would have created.
+    pub would_be: usize,
+}
+
+impl core::fmt::Display for CapExceeded {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "union would create a component of size {}",
+            self.would_be
+        )
+    }
+}
+
+impl core::error::Error for CapExceeded {}
+
+impl SizedUnionFind<usize> {
+    pub fn new(len: usize) -> Self {
+        Self {
+            uf: UnionFind::new(len),
+        }
+    }
+    /// Creates a set of singletons, allocating the parents and sizes with their exact length
+    /// and initializing both in a single pass. They are kept as separate arrays rather than
+    /// interleaved, so that finds only touch the parents.
+    pub fn new_sized(len: usize) -> Self {
+        let mut ptrs = Vec::with_capacity(len);
+        let mut sizes = Vec::with_capacity(len);
+        for i in 0..len {
+            ptrs.push(Cell::new(i));
+            sizes.push(1);
+        }
+        let uf = UnionFind {
+            ptrs,
+            sizes,
+            len,
+            halve: false,
+            growable: false,
+        };
+        Self { uf }
+    }
+}
+
+impl SizedUnionFind<u32> {
+    pub fn new_u32(len: usize) -> Self {
+        Self {
+            uf: UnionFind::<u32>::with_capacity(len),
+        }
+    }
+}
+
+impl<T: UfIndex + Ord> SizedUnionFind<T> {
+    /// Unions the components of `v` and `to`, linking the smaller under the larger. On a tie,
+    /// `to`'s root is kept.
+    pub fn set(&mut self, v: usize, to: usize) -> bool {
+        let root_v = self.uf.find(v);
+        let root_to = self.uf.find(to);
+        if root_v == root_to {
+            return false;
+        }
+        if self.uf.sizes[root_v] > self.uf.sizes[root_to] {
+            self.uf.union(root_to, root_v);
+        } else {
+            self.uf.union(root_v, root_to);
+        }
+        true
+    }
+    /// Unions the components of `absorbed` and `keep`, always keeping `keep`'s root even if it
+    /// has the smaller component.
+    pub fn union_into(&mut self, absorbed: usize, keep: usize) -> bool {
+        self.uf.union_into(absorbed, keep)
+    }
+    /// Unions the components of `v` and `to` as in [`Self::set`], only if the merged component
+    /// would have at most `max_size` elements. Returns whether a merge occurred, or the size the
+    /// merged component would have had, in which case nothing is modified.
+    pub fn union_capped(
+        &mut self,
+        v: usize,
+        to: usize,
+        max_size: usize,
+    ) -> Result<bool, CapExceeded> {
+        let root_v = self.uf.find(v);
+        let root_to = self.uf.find(to);
+        if root_v == root_to {
+            return Ok(false);
+        }
+        let would_be = self.uf.sizes[root_v].to_usize() + self.uf.sizes[root_to].to_usize();
+        if would_be > max_size {
+            return Err(CapExceeded { would_be });
+        }
+        self.set(root_v, root_to);
+        Ok(true)
+    }
+    /// Number of elements in the component containing `v`.
+    pub fn size(&self, v: usize) -> usize {
+        self.uf.size_of(v)
+    }
+    /// The underlying union-find.
+    pub fn inner(&self) -> &UnionFind<T> {
+        &self.uf
+    }
+}
+
+impl<T: UfIndex + Ord> UnionFindOp for SizedUnionFind<T> {
+    fn find(&self, v: usize) -> usize {
+        self.uf.find(v)
+    }
+    fn find_no_compress(&self, v: usize) -> usize {
+        self.uf.get(v)
+    }
+    fn is_root(&self, v: usize) -> bool {
+        self.uf.is_root(v)
+    }
+    fn union(&mut self, v: usize, to: usize) -> bool {
+        self.set(v, to)
+    }
+    fn len(&self) -> usize {
+        self.uf.len()
+    }
+    fn capacity(&self) -> usize {
+        self.uf.capacity()
+    }
+}
+
+#[test]
+fn test_union_into() {
+    let mut uf = SizedUnionFind::new_u32(8);
+    uf.union_chain(&[0, 1, 2, 3, 4]);
+    let big = uf.find(0);
+    assert!(uf.union_into(big, 7));
+    assert_eq!(uf.find(0), 7);
+    assert_eq!(uf.size(7), 6);
+    assert!(!uf.union_into(7, 3));
+    // a plain union would have kept the larger component's root.
+    uf.set(6, 5);
+    assert_eq!(uf.find(6), 5);
+    uf.set(5, 0);
+    assert_eq!(uf.find(5), 7);
+}
+
+#[test]
+fn test_union_by_size() {
+    let mut uf = SizedUnionFind::new_u32(8);
+    uf.union_chain(&[0, 1, 2, 3]);
+    assert_eq!(uf.size(0), 4);
+    let root = uf.find(0);
+    // the larger component keeps its root, regardless of argument order.
+    uf.set(root, 7);
+    assert_eq!(uf.find(7), root);
+    assert_eq!(uf.size(7), 5);
+    assert_eq!(uf.size(4), 1);
+}
+
+#[test]
+fn test_union_by_size_depth() {
+    let n = 1024;
+    let mut uf = SizedUnionFind::new(n);
+    let mut plain = UnionFind::new(n);
+    for i in 1..n {
+        uf.set(i - 1, i);
+        plain.set(i - 1, i);
+    }
+    let depth = |uf: &UnionFind, mut v: usize| {
+        let mut d = 0;
+        while uf.ptrs[v].get() != v {
+            v = uf.ptrs[v].get();
+            d += 1;
+        }
+        d
+    };
+    assert_eq!(depth(&plain, 0), n - 1);
+    let max_depth = (0..n).map(|i| depth(uf.inner(), i)).max().unwrap();
+    assert!(max_depth <= 10, "{max_depth}");
+    assert_eq!(uf.size(0), n);
+    assert!(uf.inner().same_partition(&plain));
+}
+
+#[test]
+fn test_new_sized() {
+    let mut a = SizedUnionFind::new_sized(16);
+    let mut b = SizedUnionFind::new(16);
+    assert_eq!(a, b);
+    assert!((0..16).all(|i| a.size(i) == 1));
+    for (x, y) in [(0, 1), (2, 3), (1, 3), (9, 4), (4, 15), (15, 0)] {
+        a.set(x, y);
+        b.set(x, y);
+    }
+    assert_eq!(a, b);
+    assert_eq!(a.size(9), 7);
+}
+
+#[test]
+fn test_union_capped() {
+    let mut uf = SizedUnionFind::new_u32(6);
+    assert_eq!(uf.union_capped(0, 1, 3), Ok(true));
+    assert_eq!(uf.union_capped(2, 1, 3), Ok(true));
+    assert_eq!(uf.union_capped(0, 2, 3), Ok(false));
+    uf.set(3, 4);
+    let before = uf.clone();
+    assert_eq!(uf.union_capped(4, 0, 3), Err(CapExceeded { would_be: 5 }));
+    assert_eq!(uf, before);
+    assert_eq!(uf.union_capped(4, 0, 5), Ok(true));
+    assert_eq!(uf.size(0), 5);
+}
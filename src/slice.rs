@@ -0,0 +1,141 @@
+use super::UnionFindOp;
+use core::cell::Cell;
+
+/// A union-find operating in place on an externally owned parent array, which is left holding
+/// the forest once the view is dropped.
+#[derive(Debug)]
+pub struct SliceUnionFind<'a> {
+    ptrs: &'a [Cell<u32>],
+    len: usize,
+}
+
+impl<'a> SliceUnionFind<'a> {
+    /// Views `buf` as a union-find of singletons, overwriting its contents.
+    pub fn from_slice(buf: &'a mut [u32]) -> Self {
+        assert!(
+            buf.len() < u32::MAX as usize,
+            "SliceUnionFind will overflow"
+        );
+        for (i, p) in buf.iter_mut().enumerate() {
+            *p = i as u32;
+        }
+        let len = buf.len();
+        Self {
+            ptrs: Cell::from_mut(buf).as_slice_of_cells(),
+            len,
+        }
+    }
+    /// Views `buf` as a union-find, trusting that it already holds a valid parent array where
+    /// roots point to themselves.
+    pub fn from_slice_initialized(buf: &'a mut [u32]) -> Self {
+        assert!(
+            buf.len() < u32::MAX as usize,
+            "SliceUnionFind will overflow"
+        );
+        let len = buf
+            .iter()
+            .enumerate()
+            .filter(|&(i, &p)| p as usize == i)
+            .count();
+        Self {
+            ptrs: Cell::from_mut(buf).as_slice_of_cells(),
+            len,
+        }
+    }
+    #[inline]
+    pub fn get(&self, mut v: usize) -> usize {
+        while let n = idx!(self.ptrs, v).get() as usize
+            && n != v
+        {
+            v = n;
+        }
+        v
+    }
+    #[inline]
+    pub fn get_compress(&self, v: usize) -> usize {
+        let dst = self.get(v);
+        idx!(self.ptrs, v).set(dst as u32);
+        dst
+    }
+    pub fn set(&mut self, v: usize, to: usize) -> bool {
+        let root_to = self.get_compress(to);
+        let root_v = self.get_compress(v);
+        if root_v == root_to {
+            return false;
+        }
+        idx!(self.ptrs, root_v).set(root_to as u32);
+        self.len -= 1;
+        true
+    }
+    /// Checks if a vertex is itself the root of a tree
+    pub fn is_root(&self, v: usize) -> bool {
+        self.ptrs
+            .get(v)
+            .map(|p| p.get() as usize == v)
+            .unwrap_or(false)
+    }
+    /// Points every element directly at its root.
+    pub fn compress(&mut self) {
+        for i in 0..self.ptrs.len() {
+            self.get_compress(i);
+        }
+    }
+}
+
+impl UnionFindOp for SliceUnionFind<'_> {
+    #[inline]
+    fn find(&self, v: usize) -> usize {
+        self.get_compress(v)
+    }
+    #[inline]
+    fn find_no_compress(&self, v: usize) -> usize {
+        self.get(v)
+    }
+    #[inline]
+    fn is_root(&self, v: usize) -> bool {
+        self.is_root(v)
+    }
+    #[inline]
+    fn union(&mut self, v: usize, to: usize) -> bool {
+        self.set(v, to)
+    }
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.ptrs.len()
+    }
+}
+
+#[test]
+fn test_slice_union_find() {
+    let mut buf = [u32::MAX; 8];
+    {
+        let mut uf = SliceUnionFind::from_slice(&mut buf);
+        assert_eq!(uf.len(), 8);
+        assert!(uf.set(0, 1));
+        assert!(uf.set(1, 2));
+        uf.union_chain(&[5, 6, 7]);
+        assert_eq!(uf.len(), 4);
+        assert!(uf.same_set(0, 2) && !uf.same_set(2, 5));
+        uf.compress();
+    }
+    assert_eq!(buf, [2, 2, 2, 3, 4, 7, 7, 7]);
+
+    let mut uf = SliceUnionFind::from_slice_initialized(&mut buf);
+    assert_eq!(uf.len(), 4);
+    assert!(uf.is_root(2) && !uf.is_root(0));
+    assert!(uf.set(3, 4));
+    assert_eq!(buf[3], 4);
+}
+
+#[test]
+fn test_slice_is_root_out_of_range() {
+    let mut buf = [u32::MAX; 4];
+    let uf = SliceUnionFind::from_slice(&mut buf);
+    assert!(uf.is_root(3));
+    assert!(!uf.is_root(4));
+    assert!(!uf.is_root(usize::MAX));
+}
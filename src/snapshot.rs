@@ -0,0 +1,58 @@
+use super::{UfIndex, UnionFind};
+use alloc::vec::Vec;
+
+/// The full state of a [`UnionFind`] at some point, which it can later be restored to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot<T = usize> {
+    ptrs: Vec<T>,
+    sizes: Vec<T>,
+    len: T,
+}
+
+impl<T: UfIndex> UnionFind<T> {
+    /// Copies the parent of every element, so the snapshot stays valid regardless of any
+    /// unions or compression performed before it is restored.
+    pub fn snapshot(&self) -> Snapshot<T> {
+        Snapshot {
+            ptrs: self.ptrs.iter().map(|p| p.get()).collect(),
+            sizes: self.sizes.clone(),
+            len: self.len,
+        }
+    }
+    /// Resets to exactly the state `snap` was taken from, including its capacity.
+    pub fn restore(&mut self, snap: &Snapshot<T>) {
+        self.ptrs.truncate(snap.ptrs.len());
+        for (p, &s) in self.ptrs.iter().zip(&snap.ptrs) {
+            p.set(s);
+        }
+        let n = self.ptrs.len();
+        self.ptrs
+            .extend(snap.ptrs[n..].iter().copied().map(core::cell::Cell::new));
+        self.sizes.clone_from(&snap.sizes);
+        self.len = snap.len;
+    }
+}
+
+#[test]
+fn test_snapshot_restore() {
+    use super::UnionFindOp;
+    let mut uf = UnionFind::<u32>::with_capacity(8);
+    uf.union_chain(&[0, 1, 2]);
+    let snap = uf.snapshot();
+    let before = uf.clone();
+
+    uf.union_chain(&[2, 3, 4]);
+    uf.set(6, 7);
+    uf.compress();
+    uf.extend_by(3);
+    let later = uf.snapshot();
+    uf.restore(&snap);
+    assert_eq!(uf, before);
+    assert_eq!(uf.curr_len(), 6);
+    assert!(uf.same_set(0, 2) && !uf.same_set(2, 3));
+
+    uf.restore(&later);
+    assert_eq!(uf.capacity(), 11);
+    assert_eq!(uf.curr_len(), 6);
+    assert!(uf.same_set(0, 4) && uf.same_set(6, 7));
+}
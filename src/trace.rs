@@ -0,0 +1,89 @@
+use super::{UnionFind, UnionFindOp};
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+/// A union-find which records every root change of a set of watched elements. Each union costs
+/// an extra find per watched element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TracedUnionFind {
+    uf: UnionFind<u32>,
+    /// For each watched element, the sequence of `(old_root, new_root)` transitions.
+    history: BTreeMap<usize, Vec<(usize, usize)>>,
+}
+
+impl TracedUnionFind {
+    pub fn new(len: usize) -> Self {
+        Self {
+            uf: UnionFind::<u32>::with_capacity(len),
+            history: BTreeMap::new(),
+        }
+    }
+    /// Starts tracing root changes of `v`.
+    pub fn watch(&mut self, v: usize) {
+        self.history.entry(v).or_default();
+    }
+    pub fn set(&mut self, v: usize, to: usize) -> bool {
+        let root_v = self.uf.find(v);
+        let root_to = self.uf.find(to);
+        if root_v == root_to {
+            return false;
+        }
+        let moved = self
+            .history
+            .keys()
+            .map(|&w| (w, self.uf.find(w)))
+            .filter(|&(_, r)| r == root_v || r == root_to)
+            .collect::<Vec<_>>();
+        self.uf.set(v, to);
+        let new_root = self.uf.find(to);
+        for (w, old_root) in moved {
+            if old_root != new_root {
+                self.history.get_mut(&w).unwrap().push((old_root, new_root));
+            }
+        }
+        true
+    }
+    /// The `(old_root, new_root)` transitions of a watched element, or an empty slice if it is
+    /// not watched.
+    pub fn root_history(&self, v: usize) -> &[(usize, usize)] {
+        self.history.get(&v).map(Vec::as_slice).unwrap_or(&[])
+    }
+    /// The underlying union-find.
+    pub fn inner(&self) -> &UnionFind<u32> {
+        &self.uf
+    }
+}
+
+impl UnionFindOp for TracedUnionFind {
+    fn find(&self, v: usize) -> usize {
+        self.uf.find(v)
+    }
+    fn find_no_compress(&self, v: usize) -> usize {
+        self.uf.get(v)
+    }
+    fn is_root(&self, v: usize) -> bool {
+        self.uf.is_root(v)
+    }
+    fn union(&mut self, v: usize, to: usize) -> bool {
+        self.set(v, to)
+    }
+    fn len(&self) -> usize {
+        self.uf.len()
+    }
+    fn capacity(&self) -> usize {
+        self.uf.capacity()
+    }
+}
+
+#[test]
+fn test_root_history() {
+    let mut uf = TracedUnionFind::new(6);
+    uf.watch(0);
+    uf.set(0, 1);
+    uf.set(2, 3);
+    uf.set(3, 0);
+    uf.set(4, 1);
+    uf.set(1, 5);
+    assert_eq!(uf.root_history(0), [(0, 1), (1, 5)]);
+    assert!(uf.root_history(2).is_empty());
+}
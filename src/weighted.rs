@@ -0,0 +1,102 @@
+use super::UnionFind;
+use alloc::{vec, vec::Vec};
+use core::cell::Cell;
+
+/// A union-find which also tracks the difference between the values of elements in the same
+/// component, such as for incremental difference constraints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeightedUnionFind {
+    uf: UnionFind<u32>,
+    /// `value(i) - value(parent(i))` for each element, so 0 at roots.
+    weights: Vec<Cell<i64>>,
+}
+
+impl WeightedUnionFind {
+    pub fn new(len: usize) -> Self {
+        Self {
+            uf: UnionFind::<u32>::with_capacity(len),
+            weights: vec![Cell::new(0); len],
+        }
+    }
+    /// Returns the root of `v` and `value(v) - value(root)`, pointing every element on the path
+    /// directly at the root.
+    pub fn find(&self, v: usize) -> (usize, i64) {
+        let mut path = vec![];
+        let mut root = v;
+        while let p = self.uf.ptrs[root].get() as usize
+            && p != root
+        {
+            path.push(root);
+            root = p;
+        }
+        // Walk back down from the root, so each parent's offset is known before its child's.
+        let mut offset = 0;
+        for &u in path.iter().rev() {
+            offset += self.weights[u].get();
+            self.weights[u].set(offset);
+            self.uf.ptrs[u].set(root as u32);
+        }
+        (root, offset)
+    }
+    /// Records that `value(a) - value(b) == delta`. Returns false without modifying anything if
+    /// this contradicts an existing relation.
+    pub fn union(&mut self, a: usize, b: usize, delta: i64) -> bool {
+        let (root_a, off_a) = self.find(a);
+        let (root_b, off_b) = self.find(b);
+        if root_a == root_b {
+            return off_a - off_b == delta;
+        }
+        self.uf.ptrs[root_a].set(root_b as u32);
+        self.weights[root_a].set(delta - off_a + off_b);
+        self.uf.sizes[root_b] += self.uf.sizes[root_a];
+        self.uf.len -= 1;
+        true
+    }
+    /// `value(a) - value(b)`, if `a` and `b` are in the same component.
+    pub fn diff(&self, a: usize, b: usize) -> Option<i64> {
+        let (root_a, off_a) = self.find(a);
+        let (root_b, off_b) = self.find(b);
+        (root_a == root_b).then_some(off_a - off_b)
+    }
+    /// Number of disjoint sets.
+    pub fn len(&self) -> usize {
+        self.uf.curr_len()
+    }
+    /// Number of elements, regardless of how they are grouped.
+    pub fn capacity(&self) -> usize {
+        self.uf.capacity()
+    }
+    /// Whether there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.capacity() == 0
+    }
+    /// Number of elements in the component containing `v`, without compressing.
+    pub fn size_of(&self, v: usize) -> usize {
+        self.uf.size_of(v)
+    }
+}
+
+#[test]
+fn test_weighted() {
+    let mut uf = WeightedUnionFind::new(5);
+    let (a, b, c, d) = (0, 1, 2, 3);
+    assert!(uf.union(a, b, 3));
+    assert!(uf.union(b, c, 2));
+    assert_eq!(uf.diff(a, c), Some(5));
+    assert_eq!(uf.diff(c, a), Some(-5));
+    assert_eq!(uf.diff(a, d), None);
+
+    assert!(uf.union(d, c, -1));
+    assert_eq!(uf.diff(a, d), Some(6));
+    assert!(uf.union(a, d, 6));
+    let before = uf.clone();
+    assert!(!uf.union(a, d, 4));
+    assert_eq!(uf, before);
+    assert_eq!(uf.len(), 2);
+    assert_eq!((uf.capacity(), uf.size_of(a)), (5, 4));
+
+    // Compressing must preserve offsets.
+    uf.find(a);
+    assert_eq!(uf.uf.ptrs[a].get() as usize, uf.find(c).0);
+    assert_eq!(uf.find(a).1 - uf.find(c).1, 5);
+}